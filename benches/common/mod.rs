@@ -0,0 +1,62 @@
+//! Timing shared by the benchmarks.
+//!
+//! The benchmarks run without a harness so that they need no extra
+//! dependencies. Run one with `cargo bench --bench <name>`, adding the features
+//! it names, and compare the lines it prints.
+
+// Each benchmark only uses some of these.
+#![allow(dead_code)]
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::Instant;
+
+/// The number of timed runs, of which the fastest is reported.
+const RUNS: usize = 5;
+
+/// Times `f`, which does `per` units of work, and prints the time per unit.
+///
+/// `f` is run once to warm up and then `RUNS` times, and the fastest run is
+/// reported, since slower runs are only slower because of noise.
+pub fn bench<F, T>(name: &str, per: usize, mut f: F)
+where
+    F: FnMut() -> T,
+{
+    std::hint::black_box(f());
+    let best = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap();
+    println!(
+        "{:<40} {:>10.2} ns",
+        name,
+        best.as_nanos() as f64 / per as f64
+    );
+}
+
+/// Makes `n` distinct random items which keep the most significant bit clear.
+pub fn items(n: usize) -> Vec<u32> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut items = (0..n)
+        .map(|_| rng.gen::<u32>() & !(1 << 31))
+        .collect::<Vec<u32>>();
+    items.sort_unstable();
+    items.dedup();
+    items
+}
+
+/// Shuffles `items` so that lookups do not follow the layout of the trie.
+pub fn shuffled(items: &[u32]) -> Vec<u32> {
+    use rand::seq::SliceRandom;
+    let mut items = items.to_vec();
+    items.shuffle(&mut StdRng::seed_from_u64(1));
+    items
+}
+
+/// Looks up bit `l` of `n`, starting from the least significant bit.
+pub fn lookup(n: u32, l: u32) -> bool {
+    l < 32 && (n >> l) & 1 == 1
+}
@@ -0,0 +1,30 @@
+//! Compares `BinTrieConst` with `BinTrie` at a depth of `8`.
+//!
+//! Every one of the `256` possible items is present, so every lookup walks
+//! down all `8` levels, and the items are looked up in a random order.
+
+mod common;
+
+use bintrie::{BinTrie, BinTrieConst};
+use common::{bench, lookup, shuffled};
+
+fn main() {
+    let all = (0..256).collect::<Vec<u32>>();
+    let mut dynamic = BinTrie::new_depth(8);
+    let mut fixed = BinTrieConst::<8>::new();
+    for &n in &all {
+        dynamic.insert(n, |l| lookup(n, l), lookup);
+        fixed.insert(n, |l| lookup(n, l), lookup);
+    }
+    let keys = shuffled(&all).repeat(4096);
+    bench("BinTrieConst::<8>::get", keys.len(), || {
+        keys.iter()
+            .filter_map(|&n| fixed.get(|l| lookup(n, l)))
+            .fold(0, u32::wrapping_add)
+    });
+    bench("BinTrie::get, depth 8", keys.len(), || {
+        keys.iter()
+            .filter_map(|&n| dynamic.get(|l| lookup(n, l)))
+            .fold(0, u32::wrapping_add)
+    });
+}
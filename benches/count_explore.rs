@@ -0,0 +1,23 @@
+//! Compares `count_explore` with counting the items yielded by `explore`.
+//!
+//! `count_explore` only clones the heuristic when it enters an internal node,
+//! while `explore` clones it for every slot it visits.
+
+mod common;
+
+use bintrie::{BinTrie, DepthHeuristic};
+use common::{bench, items};
+
+fn main() {
+    let trie: BinTrie = items(1 << 20).into_iter().collect();
+    for &depth in &[22, 64] {
+        let count = trie.count_explore(DepthHeuristic::new(depth));
+        assert_eq!(count, trie.explore(DepthHeuristic::new(depth)).count());
+        bench(&format!("count_explore, depth {}", depth), count, || {
+            trie.count_explore(DepthHeuristic::new(depth))
+        });
+        bench(&format!("explore count, depth {}", depth), count, || {
+            trie.explore(DepthHeuristic::new(depth)).count()
+        });
+    }
+}
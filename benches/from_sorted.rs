@@ -0,0 +1,23 @@
+//! Compares `from_sorted` with inserting the same sorted items one by one.
+//!
+//! The items are random, so that their keys are spread out, and sorted by key,
+//! which is the order `items` yields them in.
+
+mod common;
+
+use bintrie::BinTrie;
+use common::{bench, items, lookup};
+
+fn main() {
+    let sorted = items(1 << 22)
+        .into_iter()
+        .collect::<BinTrie>()
+        .items()
+        .collect::<Vec<u32>>();
+    bench("from_sorted", sorted.len(), || {
+        BinTrie::from_sorted(sorted.iter().cloned(), 8192, lookup)
+    });
+    bench("collect", sorted.len(), || {
+        sorted.iter().cloned().collect::<BinTrie>()
+    });
+}
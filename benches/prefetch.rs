@@ -0,0 +1,27 @@
+//! Measures `get` over a large trie, to compare with and without prefetching.
+//!
+//! Run it once as `cargo bench --bench prefetch` and once with
+//! `--features prefetch`. The trie is far larger than the caches and the items
+//! are looked up in a random order, so most of the internal nodes on the way
+//! down have to be loaded from memory.
+
+mod common;
+
+use bintrie::BinTrie;
+use common::{bench, items, lookup, shuffled};
+
+fn main() {
+    let items = items(1 << 23);
+    let trie = items.iter().cloned().collect::<BinTrie>();
+    let keys = shuffled(&items);
+    let name = if cfg!(feature = "prefetch") {
+        "get with prefetch"
+    } else {
+        "get without prefetch"
+    };
+    bench(name, keys.len(), || {
+        keys.iter()
+            .filter_map(|&n| trie.get(|l| lookup(n, l)))
+            .fold(0, u32::wrapping_add)
+    });
+}
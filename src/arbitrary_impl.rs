@@ -0,0 +1,29 @@
+use crate::{BinTrie, HIGH};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Generates a trie by inserting arbitrary items into a trie of arbitrary depth.
+///
+/// The depth is between `1` and `64` and the items have their most significant
+/// bit cleared. Every item is inserted with `insert_self_keyed`, so the key and
+/// the lookup always agree and the trie passes `verify`. Items may collide at
+/// shallow depths, in which case the last one is kept.
+///
+/// This requires the `arbitrary` feature.
+///
+/// ```
+/// # use bintrie::BinTrie;
+/// use arbitrary::{Arbitrary, Unstructured};
+/// let data = (0..=255).collect::<Vec<u8>>();
+/// let trie = BinTrie::arbitrary(&mut Unstructured::new(&data)).unwrap();
+/// assert!(trie.verify().is_ok());
+/// assert!(!trie.is_empty());
+/// ```
+impl<'a> Arbitrary<'a> for BinTrie {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut trie = BinTrie::new_depth(u.int_in_range(1..=64)?);
+        for item in u.arbitrary_iter::<u32>()? {
+            trie.insert_self_keyed(item? & !HIGH);
+        }
+        Ok(trie)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{BacktrackHeuristic, BinTrie, HIGH};
+use alloc::{vec, vec::Vec};
+use core::iter::FusedIterator;
+
+impl BinTrie {
+    /// Iterates over the trie like `explore`, updating a single heuristic in place.
+    ///
+    /// `explore` clones the heuristic at every internal node it enters, which is
+    /// costly if the heuristic holds a lot of state. Here the heuristic is never
+    /// cloned: `enter` is called when descending into an internal node and
+    /// `leave` when going back up, so the heuristic must be able to undo each
+    /// step. Items are yielded in the same order as `explore` with an equivalent
+    /// heuristic.
+    ///
+    /// ```
+    /// # use bintrie::{BacktrackHeuristic, BinTrie, DepthHeuristic, FilterHeuristic};
+    /// /// Allows at most `budget` `true` sides along a path.
+    /// struct Budget {
+    ///     spent: Vec<bool>,
+    ///     budget: usize,
+    /// }
+    ///
+    /// impl BacktrackHeuristic for Budget {
+    ///     type Iter = std::vec::IntoIter<bool>;
+    ///
+    ///     fn enter(&mut self, side: bool) {
+    ///         self.spent.push(side);
+    ///     }
+    ///
+    ///     fn leave(&mut self, _: bool) {
+    ///         self.spent.pop();
+    ///     }
+    ///
+    ///     fn iter(&self) -> Self::Iter {
+    ///         if self.spent.iter().filter(|&&side| side).count() < self.budget {
+    ///             vec![false, true].into_iter()
+    ///         } else {
+    ///             vec![false].into_iter()
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let trie: BinTrie = (0..16).collect();
+    /// let mut items = trie
+    ///     .explore_backtracking(Budget { spent: vec![], budget: 1 })
+    ///     .collect::<Vec<u32>>();
+    /// items.sort();
+    /// assert_eq!(items, vec![0, 1, 2, 4, 8]);
+    /// assert!(trie
+    ///     .explore_backtracking(DepthHeuristic::new(4))
+    ///     .eq(trie.explore(DepthHeuristic::new(4))));
+    /// ```
+    pub fn explore_backtracking<'a, H>(
+        &'a self,
+        heuristic: H,
+    ) -> impl FusedIterator<Item = u32> + 'a
+    where
+        H: BacktrackHeuristic + 'a,
+    {
+        let iter = heuristic.iter();
+        BacktrackIter {
+            trie: self,
+            heuristic,
+            indices: vec![(&self.internals[0].0, iter, false)],
+        }
+    }
+}
+
+struct BacktrackIter<'a, H>
+where
+    H: BacktrackHeuristic,
+{
+    trie: &'a BinTrie,
+    heuristic: H,
+    /// The internal nodes being explored, the sides left to explore in each,
+    /// and the side that was taken to reach it.
+    indices: Vec<(&'a [u32; 2], H::Iter, bool)>,
+}
+
+impl<'a, H> Iterator for BacktrackIter<'a, H>
+where
+    H: BacktrackHeuristic,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (array, iter, side) = self.indices.last_mut()?;
+            let choice = match iter.next() {
+                Some(choice) => choice,
+                None => {
+                    // Leave the node, except for the root, which was never entered.
+                    let side = *side;
+                    self.indices.pop();
+                    if !self.indices.is_empty() {
+                        self.heuristic.leave(side);
+                    }
+                    continue;
+                }
+            };
+            match array[if choice { 1 } else { 0 }] {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => return Some(self.trie.untag(n)),
+                // Internal node
+                n => {
+                    self.heuristic.enter(choice);
+                    let iter = self.heuristic.iter();
+                    self.indices
+                        .push((&self.trie.internals[n as usize].0, iter, choice));
+                }
+            }
+        }
+    }
+}
+
+/// Once the stack is empty nothing can be pushed to it again.
+impl<'a, H> FusedIterator for BacktrackIter<'a, H> where H: BacktrackHeuristic {}
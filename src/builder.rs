@@ -0,0 +1,98 @@
+use crate::{BinTrie, HIGH};
+use core::fmt;
+
+/// An error produced by `BinTrieBuilder::build` when the configuration is invalid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// The depth is `0`.
+    ZeroDepth,
+    /// The capacity is more internal nodes than the trie can index.
+    CapacityTooLarge(usize),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::ZeroDepth => write!(f, "depth must be greater than 0"),
+            BuildError::CapacityTooLarge(capacity) => write!(
+                f,
+                "capacity of {} internal nodes is more than can be indexed",
+                capacity
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Configures a `BinTrie` before making it.
+///
+/// The configuration is only checked by `build`, which returns an error rather
+/// than panicking like `BinTrie::new_depth`.
+///
+/// ```
+/// # use bintrie::{BinTrie, BuildError};
+/// let trie = BinTrie::builder().depth(32).capacity(1024).build().unwrap();
+/// assert_eq!(trie.depth(), 32);
+/// assert!(trie.capacity() >= 1024);
+/// assert_eq!(BinTrie::builder().depth(0).build().unwrap_err(), BuildError::ZeroDepth);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct BinTrieBuilder {
+    depth: u32,
+    capacity: usize,
+}
+
+impl BinTrieBuilder {
+    /// Makes a builder with a maximum `depth` of `8192` and no extra capacity.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum depth of the trie.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the number of internal nodes to allocate space for up front.
+    pub fn capacity(mut self, internal_nodes: usize) -> Self {
+        self.capacity = internal_nodes;
+        self
+    }
+
+    /// Makes the trie, checking that the configuration is valid.
+    pub fn build(self) -> Result<BinTrie, BuildError> {
+        if self.depth == 0 {
+            return Err(BuildError::ZeroDepth);
+        }
+        // Indices of internal nodes may not use the most significant bit.
+        if self.capacity > HIGH as usize {
+            return Err(BuildError::CapacityTooLarge(self.capacity));
+        }
+        Ok(BinTrie::new_depth_capacity(self.depth, self.capacity))
+    }
+}
+
+impl Default for BinTrieBuilder {
+    fn default() -> Self {
+        Self {
+            depth: 8192,
+            capacity: 0,
+        }
+    }
+}
+
+impl BinTrie {
+    /// Makes a builder to configure a new trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let trie = BinTrie::builder().depth(128).build().unwrap();
+    /// assert_eq!(trie.depth(), 128);
+    /// ```
+    pub fn builder() -> BinTrieBuilder {
+        BinTrieBuilder::new()
+    }
+}
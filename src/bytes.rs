@@ -0,0 +1,331 @@
+use crate::{BinTrie, CollisionPolicy, Internal, VerifyError, HIGH};
+use alloc::vec::Vec;
+use core::{fmt, mem, slice};
+
+/// The magic bytes at the start of the output of `BinTrie::to_bytes`.
+const MAGIC: [u8; 4] = *b"BTRI";
+/// The version of the format written by `BinTrie::to_bytes`.
+const VERSION: u32 = 2;
+/// The size of the header (magic, version, depth, tag bit, and internal node count).
+const HEADER_LEN: usize = 20;
+/// The size of the header of version `1`, which has no tag bit.
+const HEADER_LEN_V1: usize = 16;
+
+/// An error produced by `BinTrie::from_bytes` when the input is not a valid trie.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FromBytesError {
+    /// The buffer ended before the header or the internal nodes were complete.
+    Truncated,
+    /// The buffer has bytes left over after the internal nodes.
+    TrailingBytes,
+    /// The buffer does not start with the expected magic bytes.
+    BadMagic,
+    /// The format version is not supported by this version of the crate.
+    UnsupportedVersion(u32),
+    /// The depth is `0`.
+    ZeroDepth,
+    /// The tag bit is not less than `32`.
+    InvalidTagBit(u32),
+    /// There are no internal nodes, so the root is missing.
+    MissingRoot,
+    /// A slot of an internal node points to an internal node that does not exist.
+    InvalidIndex {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// A slot points to an internal node which was already reached through
+    /// another slot, which includes any cycle back up the trie.
+    Revisited {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// A slot points to an internal node which would sit past the maximum depth.
+    TooDeep {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// Internal nodes which cannot be reached from the root hold this many leaves.
+    UnreachableLeaves(usize),
+}
+
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FromBytesError::Truncated => write!(f, "buffer is truncated"),
+            FromBytesError::TrailingBytes => write!(f, "buffer has trailing bytes"),
+            FromBytesError::BadMagic => write!(f, "buffer does not start with the magic bytes"),
+            FromBytesError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            FromBytesError::ZeroDepth => write!(f, "depth must be greater than 0"),
+            FromBytesError::InvalidTagBit(tag_bit) => {
+                write!(f, "tag bit {} must be less than 32", tag_bit)
+            }
+            FromBytesError::MissingRoot => write!(f, "missing root node"),
+            FromBytesError::InvalidIndex { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points to a missing node",
+                slot, node
+            ),
+            FromBytesError::Revisited { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points to an already reachable node",
+                slot, node
+            ),
+            FromBytesError::TooDeep { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points past the maximum depth",
+                slot, node
+            ),
+            FromBytesError::UnreachableLeaves(leaves) => {
+                write!(f, "{} leaves cannot be reached from the root", leaves)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromBytesError {}
+
+impl BinTrie {
+    /// Encodes the trie in a compact binary format.
+    ///
+    /// The format is a `20` byte header (the magic bytes `BTRI`, a format version,
+    /// the depth, the tag bit, and the number of internal nodes) followed by the
+    /// slots of every internal node. All numbers are little-endian `u32`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie = BinTrie::new_depth(32);
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// for n in 0..100 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// let trie = BinTrie::from_bytes(&trie.to_bytes()).unwrap();
+    /// assert_eq!(trie.len(), 100);
+    /// assert_eq!(trie.get(|l| lookup(42, l)), Some(42));
+    ///
+    /// // The tag bit is kept, so the items read back are the ones inserted.
+    /// let mut trie = BinTrie::new_depth_tag_bit(31, 4);
+    /// trie.insert(1 << 31, |_| true, |_, _| true);
+    /// let trie = BinTrie::from_bytes(&trie.to_bytes()).unwrap();
+    /// assert_eq!(trie.tag_bit(), 4);
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![1 << 31]);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.internals.len() * 8);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.depth.to_le_bytes());
+        bytes.extend_from_slice(&self.tag_bit.to_le_bytes());
+        bytes.extend_from_slice(&(self.internals.len() as u32).to_le_bytes());
+        for &n in self.internals.iter().flat_map(|internal| internal.0.iter()) {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a trie from the format produced by `to_bytes`.
+    ///
+    /// This rejects truncated buffers and any trie that `verify` would reject:
+    /// internal node indices which are out of bounds, internal nodes reachable
+    /// through more than one slot or past the maximum depth, and leaves in nodes
+    /// that cannot be reached from the root. This makes it safe to use on
+    /// untrusted input.
+    ///
+    /// Buffers written in version `1` of the format, which had no tag bit, are
+    /// still accepted and load with the default tag bit of `31`.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, FromBytesError};
+    /// let mut trie = BinTrie::new();
+    /// trie.insert(3, |_| false, |_, _| false);
+    /// trie.insert(5, |n| n == 1, |i, n| i == 5 && n == 1);
+    /// let mut bytes = trie.to_bytes();
+    /// assert_eq!(
+    ///     BinTrie::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+    ///     FromBytesError::Truncated
+    /// );
+    /// // Point the first slot of the root at a node that does not exist.
+    /// bytes[20..24].copy_from_slice(&7u32.to_le_bytes());
+    /// assert_eq!(
+    ///     BinTrie::from_bytes(&bytes).unwrap_err(),
+    ///     FromBytesError::InvalidIndex { node: 0, slot: 0 }
+    /// );
+    ///
+    /// // Point the first slot of the root at a second node, which points to itself.
+    /// let mut bytes = BinTrie::new().to_bytes();
+    /// bytes.truncate(16);
+    /// bytes.extend_from_slice(&2u32.to_le_bytes());
+    /// for slot in &[1u32, 0, 1, 0] {
+    ///     bytes.extend_from_slice(&slot.to_le_bytes());
+    /// }
+    /// assert_eq!(
+    ///     BinTrie::from_bytes(&bytes).unwrap_err(),
+    ///     FromBytesError::Revisited { node: 1, slot: 0 }
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        if bytes.len() < HEADER_LEN_V1 {
+            return Err(FromBytesError::Truncated);
+        }
+        let word = |i: usize| {
+            let mut word = [0; 4];
+            word.copy_from_slice(&bytes[i * 4..i * 4 + 4]);
+            u32::from_le_bytes(word)
+        };
+        if bytes[0..4] != MAGIC {
+            return Err(FromBytesError::BadMagic);
+        }
+        let version = word(1);
+        let header_len = match version {
+            1 => HEADER_LEN_V1,
+            VERSION => HEADER_LEN,
+            _ => return Err(FromBytesError::UnsupportedVersion(version)),
+        };
+        if bytes.len() < header_len {
+            return Err(FromBytesError::Truncated);
+        }
+        let depth = word(2);
+        let tag_bit = if version == 1 { 31 } else { word(3) };
+        let count = word(header_len / 4 - 1) as usize;
+        let body = &bytes[header_len..];
+        if body.len() / 8 < count {
+            return Err(FromBytesError::Truncated);
+        }
+        if body.len() != count * 8 {
+            return Err(FromBytesError::TrailingBytes);
+        }
+        let internals = (0..count)
+            .map(|i| {
+                let i = header_len / 4 + 2 * i;
+                Internal([word(i), word(i + 1)])
+            })
+            .collect();
+        Self::from_internals(internals, depth, tag_bit)
+    }
+
+    /// Gets the internal nodes of the trie, with the root first.
+    ///
+    /// Each slot is `0` if it is empty, a leaf tagged with the most significant
+    /// bit, or the index of another internal node. A leaf holds its item with the
+    /// trie's tag bit (see `tag_bit`) swapped into the most significant bit, as
+    /// described in `new_depth_tag_bit`. This can be written out directly and turned back into
+    /// a trie with `from_raw`, along with the depth and the tag bit.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie = BinTrie::new();
+    /// trie.insert(3, |_| false, |_, _| false);
+    /// assert_eq!(trie.as_raw(), &[[3 | 1 << 31, 0]]);
+    /// ```
+    pub fn as_raw(&self) -> &[[u32; 2]] {
+        // `Internal` is `repr(transparent)`, so it has the same layout as `[u32; 2]`.
+        unsafe {
+            slice::from_raw_parts(
+                self.internals.as_ptr() as *const [u32; 2],
+                self.internals.len(),
+            )
+        }
+    }
+
+    /// Makes a trie from the internal nodes produced by `as_raw`, without copying them.
+    ///
+    /// The number of items is found by counting the leaf slots of every node, so
+    /// unreachable nodes must not contain leaves.
+    ///
+    /// # Safety
+    ///
+    /// `nodes` must not be empty, `depth` must not be `0` and `tag_bit` must be
+    /// less than `32`. Every slot which is
+    /// not empty or a leaf must hold the index of a node in `nodes` which is not
+    /// the root, no node may be reachable through more than one slot, and no path
+    /// may be longer than `depth`. The other methods skip bounds checks based on
+    /// these invariants. Call `from_bytes` instead for untrusted input.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let trie: BinTrie = (0..100).collect();
+    /// let nodes = trie.as_raw().to_vec();
+    /// let copy = unsafe { BinTrie::from_raw(nodes, trie.depth(), trie.tag_bit()) };
+    /// assert_eq!(copy.verify(), Ok(()));
+    /// assert_eq!(copy, trie);
+    /// ```
+    pub unsafe fn from_raw(nodes: Vec<[u32; 2]>, depth: u32, tag_bit: u32) -> Self {
+        let len = nodes
+            .iter()
+            .flat_map(|node| node.iter())
+            .filter(|&&n| n & HIGH != 0)
+            .count();
+        let mut nodes = mem::ManuallyDrop::new(nodes);
+        // `Internal` is `repr(transparent)`, so it has the same layout as `[u32; 2]`.
+        let internals = Vec::from_raw_parts(
+            nodes.as_mut_ptr() as *mut Internal,
+            nodes.len(),
+            nodes.capacity(),
+        );
+        Self {
+            internals,
+            depth,
+            len,
+            free: Vec::new(),
+            journal: None,
+            collision: CollisionPolicy::default(),
+            tag_bit,
+        }
+    }
+
+    /// Builds a trie from raw parts, checking every invariant that `verify` checks.
+    pub(crate) fn from_internals(
+        internals: Vec<Internal>,
+        depth: u32,
+        tag_bit: u32,
+    ) -> Result<Self, FromBytesError> {
+        if depth == 0 {
+            return Err(FromBytesError::ZeroDepth);
+        }
+        if tag_bit >= 32 {
+            return Err(FromBytesError::InvalidTagBit(tag_bit));
+        }
+        if internals.is_empty() {
+            return Err(FromBytesError::MissingRoot);
+        }
+        let mut len = 0;
+        for (node, internal) in internals.iter().enumerate() {
+            for (slot, &n) in internal.0.iter().enumerate() {
+                if n & HIGH != 0 {
+                    len += 1;
+                } else if n as usize >= internals.len() {
+                    return Err(FromBytesError::InvalidIndex { node, slot });
+                }
+            }
+        }
+        let trie = Self {
+            internals,
+            depth,
+            len,
+            free: Vec::new(),
+            journal: None,
+            collision: CollisionPolicy::default(),
+            tag_bit,
+        };
+        // Every leaf was counted, so the reachable ones only fall short of
+        // `len` if some nodes cannot be reached.
+        trie.verify().map_err(|error| match error {
+            VerifyError::MissingRoot => FromBytesError::MissingRoot,
+            VerifyError::InvalidIndex { node, slot } => FromBytesError::InvalidIndex { node, slot },
+            VerifyError::Revisited { node, slot } => FromBytesError::Revisited { node, slot },
+            VerifyError::TooDeep { node, slot } => FromBytesError::TooDeep { node, slot },
+            VerifyError::LenMismatch { len, leaves } => {
+                FromBytesError::UnreachableLeaves(len - leaves)
+            }
+        })?;
+        Ok(trie)
+    }
+}
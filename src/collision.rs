@@ -0,0 +1,54 @@
+use crate::BinTrie;
+
+/// Decides what `BinTrie::insert` does when two items collide at the maximum depth.
+///
+/// Items collide when their keys are the same for every level of the trie, so
+/// they need the same slot. With a shallow depth this happens often, so the
+/// policy controls which item is lost.
+///
+/// ```
+/// # use bintrie::{BinTrie, BinTrieError, CollisionPolicy, Inserted};
+/// let mut trie = BinTrie::new_depth(1);
+/// trie.insert(1, |_| true, |_, _| true);
+///
+/// trie.set_collision_policy(CollisionPolicy::KeepFirst);
+/// assert_eq!(trie.insert(3, |_| true, |_, _| true), Inserted::Discarded { kept: 1 });
+/// assert_eq!(trie.get(|_| true), Some(1));
+///
+/// trie.set_collision_policy(CollisionPolicy::Reject);
+/// assert_eq!(trie.try_insert(3, |_| true, |_, _| true), Err(BinTrieError::Collision));
+/// assert_eq!(trie.get(|_| true), Some(1));
+///
+/// trie.set_collision_policy(CollisionPolicy::KeepLast);
+/// assert_eq!(trie.insert(3, |_| true, |_, _| true), Inserted::DepthExhausted { replaced: 1 });
+/// assert_eq!(trie.get(|_| true), Some(3));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CollisionPolicy {
+    /// Keeps the item already in the trie, returning `Inserted::Discarded`.
+    KeepFirst,
+    /// Replaces the item already in the trie, returning `Inserted::DepthExhausted`.
+    ///
+    /// This is the default.
+    #[default]
+    KeepLast,
+    /// Keeps the item already in the trie, returning `BinTrieError::Collision`.
+    ///
+    /// This makes `insert` panic, so use `try_insert` to handle collisions.
+    Reject,
+}
+
+impl BinTrie {
+    /// The policy for items that collide at the maximum depth.
+    pub fn collision_policy(&self) -> CollisionPolicy {
+        self.collision
+    }
+
+    /// Sets the policy for items that collide at the maximum depth.
+    ///
+    /// The policy only affects later insertions. It is not serialized, so a
+    /// deserialized trie uses the default of `CollisionPolicy::KeepLast`.
+    pub fn set_collision_policy(&mut self, policy: CollisionPolicy) {
+        self.collision = policy;
+    }
+}
@@ -0,0 +1,386 @@
+use crate::Inserted;
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::{mem, slice};
+
+/// A slot of a `CowInternal`.
+#[derive(Clone, Debug)]
+enum CowSlot {
+    Empty,
+    Leaf(u32),
+    Internal(Arc<CowInternal>),
+}
+
+/// Contains the 2 children of an internal node, which may be shared with other tries.
+#[derive(Clone, Debug)]
+struct CowInternal([CowSlot; 2]);
+
+impl Default for CowInternal {
+    fn default() -> Self {
+        Self([CowSlot::Empty, CowSlot::Empty])
+    }
+}
+
+impl CowInternal {
+    /// Moves the internal nodes below this one onto `stack`, leaving their slots empty.
+    fn unlink(&mut self, stack: &mut Vec<Arc<CowInternal>>) {
+        for slot in &mut self.0 {
+            if let CowSlot::Internal(child) = mem::replace(slot, CowSlot::Empty) {
+                stack.push(child);
+            }
+        }
+    }
+}
+
+/// Drops a slot without recursing into the internal nodes below it.
+///
+/// The derived drop recurses once per level, which can overflow the stack for
+/// a chain of internal nodes as deep as the trie. Nodes still shared with
+/// another trie only have their reference count decremented.
+fn drop_slot(slot: CowSlot) {
+    let mut stack = match slot {
+        CowSlot::Internal(node) => vec![node],
+        _ => return,
+    };
+    while let Some(node) = stack.pop() {
+        if let Ok(mut node) = Arc::try_unwrap(node) {
+            node.unlink(&mut stack);
+        }
+    }
+}
+
+/// A trie whose internal nodes are shared between clones until they are modified.
+///
+/// Every internal node is reference counted, so `clone` only copies the root
+/// pointer. Modifying a trie copies the shared nodes along the path it changes,
+/// while the rest stay shared. A reader can hold a clone as a snapshot while a
+/// writer keeps modifying its own copy, and neither one blocks the other.
+///
+/// This is an opt-in alternative to `BinTrie`, which keeps every node in one
+/// `Vec` and indexes it with `u32`. Here every node is a separate allocation
+/// behind an `Arc`, which makes lookups chase pointers and updates adjust
+/// reference counts, so it is slower when nothing is shared. Leaves are not
+/// tagged, so items may use all `32` bits.
+///
+/// ```
+/// # use bintrie::CowBinTrie;
+/// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+/// let mut trie = CowBinTrie::new();
+/// for n in 0..100 {
+///     trie.insert(n, |l| lookup(n, l), lookup);
+/// }
+/// let snapshot = trie.clone();
+/// trie.insert(1000, |l| lookup(1000, l), lookup);
+/// trie.remove(5, |l| lookup(5, l));
+/// assert_eq!(snapshot.len(), 100);
+/// assert_eq!(snapshot.get(|l| lookup(5, l)), Some(5));
+/// assert_eq!(trie.get(|l| lookup(1000, l)), Some(1000));
+/// assert_eq!(trie.len(), 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CowBinTrie {
+    root: Arc<CowInternal>,
+    /// The maximum depth to stop at.
+    depth: u32,
+    /// The number of items in the trie.
+    len: usize,
+}
+
+impl CowBinTrie {
+    /// Makes a new trie with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::CowBinTrie;
+    /// let trie = CowBinTrie::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new trie with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::CowBinTrie;
+    /// let trie = CowBinTrie::new_depth(128);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        assert!(depth > 0);
+        Self {
+            root: Arc::new(CowInternal::default()),
+            depth,
+            len: 0,
+        }
+    }
+
+    /// The maximum depth of the trie.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts any number.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// Every internal node on the key's path which is shared with another trie is
+    /// copied before it is modified.
+    ///
+    /// ```
+    /// # use bintrie::{CowBinTrie, Inserted};
+    /// let mut trie = CowBinTrie::new_depth(2);
+    /// assert_eq!(trie.insert(u32::MAX, |_| true, |_, _| true), Inserted::New);
+    /// assert_eq!(trie.insert(u32::MAX, |_| true, |_, _| true), Inserted::AlreadyPresent);
+    /// assert_eq!(
+    ///     trie.insert(6, |_| true, |_, _| true),
+    ///     Inserted::DepthExhausted { replaced: u32::MAX }
+    /// );
+    /// ```
+    pub fn insert<K, F>(&mut self, item: u32, mut key: K, mut lookup: F) -> Inserted
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u32, u32) -> bool,
+    {
+        let mut node = Arc::make_mut(&mut self.root);
+        for i in 0..self.depth - 1 {
+            let slot = &mut node.0[if key(i) { 1 } else { 0 }];
+            match slot {
+                // Empty node encountered.
+                CowSlot::Empty => {
+                    *slot = CowSlot::Leaf(item);
+                    self.len += 1;
+                    return Inserted::New;
+                }
+                // The item itself is already present.
+                CowSlot::Leaf(m) if *m == item => return Inserted::AlreadyPresent,
+                // Leaf node encountered.
+                CowSlot::Leaf(m) => {
+                    // Move the existing leaf into a new internal node.
+                    let m = *m;
+                    let mut new_internal = CowInternal::default();
+                    new_internal.0[if lookup(m, i + 1) { 1 } else { 0 }] = CowSlot::Leaf(m);
+                    *slot = CowSlot::Internal(Arc::new(new_internal));
+                }
+                // Internal node encountered.
+                CowSlot::Internal(_) => {}
+            }
+            // Copy the internal node if it is shared, then move to it.
+            node = match slot {
+                CowSlot::Internal(child) => Arc::make_mut(child),
+                _ => unreachable!(),
+            };
+        }
+
+        // For the last bit we replace whatever occupies the space.
+        let slot = &mut node.0[if key(self.depth - 1) { 1 } else { 0 }];
+        match mem::replace(slot, CowSlot::Leaf(item)) {
+            CowSlot::Leaf(m) if m == item => Inserted::AlreadyPresent,
+            CowSlot::Leaf(m) => Inserted::DepthExhausted { replaced: m },
+            _ => {
+                self.len += 1;
+                Inserted::New
+            }
+        }
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::CowBinTrie;
+    /// let mut trie = CowBinTrie::new();
+    /// trie.insert(u32::MAX, |_| true, |_, _| true);
+    /// assert_eq!(trie.get(|_| true), Some(u32::MAX));
+    /// assert_eq!(trie.get(|_| false), None);
+    /// ```
+    pub fn get<K>(&self, mut key: K) -> Option<u32>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        let mut node = &*self.root;
+        for i in 0..self.depth {
+            match &node.0[if key(i) { 1 } else { 0 }] {
+                // Empty node encountered.
+                CowSlot::Empty => return None,
+                // Leaf node encountered.
+                CowSlot::Leaf(m) => return Some(*m),
+                // Internal node encountered.
+                CowSlot::Internal(child) => node = child,
+            }
+        }
+        None
+    }
+
+    /// Removes a previously inserted item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// Returns `true` if `item` was found at the end of the key's path and removed.
+    /// Lone leaves are moved up like with `BinTrie::remove`. The path is checked
+    /// before anything is modified, so nothing is copied if the item is not found,
+    /// and only the internal nodes down to the highest one that changes are copied.
+    ///
+    /// ```
+    /// # use bintrie::CowBinTrie;
+    /// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+    /// let mut trie = CowBinTrie::new();
+    /// for n in 0..4 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert!(trie.remove(2, |l| lookup(2, l)));
+    /// assert!(!trie.remove(2, |l| lookup(2, l)));
+    /// assert_eq!(trie.len(), 3);
+    /// assert_eq!(trie.get(|l| lookup(0, l)), Some(0));
+    /// ```
+    pub fn remove<K>(&mut self, item: u32, mut key: K) -> bool
+    where
+        K: FnMut(u32) -> bool,
+    {
+        // The position taken in every internal node down to the leaf, along
+        // with the leaf in the other slot, if that slot holds one.
+        let mut path = Vec::new();
+        let mut node = &*self.root;
+        loop {
+            if path.len() as u32 == self.depth {
+                return false;
+            }
+            let position = if key(path.len() as u32) { 1 } else { 0 };
+            let sibling = match node.0[1 - position] {
+                CowSlot::Empty => Some(None),
+                CowSlot::Leaf(m) => Some(Some(m)),
+                CowSlot::Internal(_) => None,
+            };
+            path.push((position, sibling));
+            match &node.0[position] {
+                // Only remove the leaf if it is actually the item.
+                CowSlot::Leaf(m) if *m == item => break,
+                CowSlot::Internal(child) => node = child,
+                _ => return false,
+            }
+        }
+
+        // Find the highest slot that changes, walking up while the internal node
+        // below is left with at most one leaf. The root is never removed.
+        let mut replacement = None;
+        while path.len() > 1 {
+            let sibling = match path.last().unwrap().1 {
+                Some(sibling) => sibling,
+                None => break,
+            };
+            match (replacement, sibling) {
+                (Some(_), Some(_)) => break,
+                (None, sibling) | (sibling, None) => replacement = sibling,
+            }
+            path.pop();
+        }
+
+        let (&(position, _), above) = path.split_last().unwrap();
+        let mut node = Arc::make_mut(&mut self.root);
+        for &(position, _) in above {
+            node = match &mut node.0[position] {
+                CowSlot::Internal(child) => Arc::make_mut(child),
+                _ => unreachable!(),
+            };
+        }
+        drop_slot(mem::replace(
+            &mut node.0[position],
+            replacement.map_or(CowSlot::Empty, CowSlot::Leaf),
+        ));
+        self.len -= 1;
+        true
+    }
+
+    /// Get an iterator over the items added to the trie.
+    ///
+    /// The items are yielded in the same order as `BinTrie::items`.
+    ///
+    /// ```
+    /// # use bintrie::CowBinTrie;
+    /// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+    /// let mut trie = CowBinTrie::new();
+    /// for n in 0..4 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![0, 2, 1, 3]);
+    /// ```
+    pub fn items(&self) -> impl Iterator<Item = u32> + '_ {
+        CowIter {
+            indices: vec![self.root.0.iter()],
+        }
+    }
+}
+
+impl Default for CowBinTrie {
+    fn default() -> Self {
+        Self::new_depth(8192)
+    }
+}
+
+/// Drops the internal nodes without recursing, so deep tries do not overflow the stack.
+///
+/// ```
+/// # use bintrie::CowBinTrie;
+/// // Two items which only differ at the last levels make a chain of internal
+/// // nodes as deep as the trie, which is dropped on a small stack.
+/// std::thread::Builder::new()
+///     .stack_size(64 * 1024)
+///     .spawn(|| {
+///         let lookup = |n: u32, l: u32| n == 1 && l == 8190;
+///         let mut trie = CowBinTrie::new();
+///         trie.insert(0, |l| lookup(0, l), lookup);
+///         trie.insert(1, |l| lookup(1, l), lookup);
+///         let snapshot = trie.clone();
+///         assert!(trie.remove(1, |l| lookup(1, l)));
+///         drop(trie);
+///         assert_eq!(snapshot.get(|l| lookup(1, l)), Some(1));
+///     })
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// ```
+impl Drop for CowBinTrie {
+    fn drop(&mut self) {
+        if let Some(root) = Arc::get_mut(&mut self.root) {
+            let mut stack = Vec::new();
+            root.unlink(&mut stack);
+            for node in stack {
+                drop_slot(CowSlot::Internal(node));
+            }
+        }
+    }
+}
+
+struct CowIter<'a> {
+    indices: Vec<slice::Iter<'a, CowSlot>>,
+}
+
+impl<'a> Iterator for CowIter<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut current = self.indices.pop()?;
+            let slot = if let Some(slot) = current.next() {
+                self.indices.push(current);
+                slot
+            } else {
+                continue;
+            };
+            match slot {
+                CowSlot::Empty => {}
+                CowSlot::Leaf(m) => return Some(*m),
+                CowSlot::Internal(child) => self.indices.push(child.0.iter()),
+            }
+        }
+    }
+}
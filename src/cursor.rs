@@ -0,0 +1,102 @@
+use crate::{BinTrie, HIGH};
+use alloc::{vec, vec::Vec};
+
+/// What a `Cursor` found on the side it tried to descend into.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// The side is empty, so the cursor stays where it is.
+    Empty,
+    /// The side holds a leaf with this item, so the cursor stays where it is.
+    Leaf(u32),
+    /// The side holds an internal node, which the cursor moved to.
+    Internal,
+}
+
+/// A read-only position in a trie which can be moved down and back up manually.
+///
+/// The cursor starts at the root and keeps the internal nodes it walked through,
+/// so it can back up and take another side without walking down from the root
+/// again. This allows several lookups for keys with a shared prefix to walk the
+/// prefix only once.
+///
+/// ```
+/// # use bintrie::{BinTrie, Step};
+/// let trie: BinTrie = (0..8).collect();
+/// let mut cursor = trie.cursor();
+/// // Items are keyed from their least significant bit, so walk the shared `1` once.
+/// assert_eq!(cursor.descend(true), Step::Internal);
+/// for &item in &[1, 3, 5, 7] {
+///     assert_eq!(cursor.descend(item & 2 != 0), Step::Internal);
+///     assert_eq!(cursor.descend(item & 4 != 0), Step::Leaf(item));
+///     cursor.ascend();
+/// }
+/// assert_eq!(cursor.level(), 1);
+/// cursor.reset();
+/// assert_eq!(cursor.level(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cursor<'a> {
+    trie: &'a BinTrie,
+    /// The internal nodes from the root to the current one.
+    path: Vec<u32>,
+}
+
+impl BinTrie {
+    /// Makes a `Cursor` positioned at the root of the trie.
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor {
+            trie: self,
+            path: vec![0],
+        }
+    }
+}
+
+impl<'a> Cursor<'a> {
+    /// The number of internal nodes walked through to reach the current one.
+    ///
+    /// This is `0` at the root, and is also the level of the key bit that the
+    /// next `descend` takes.
+    pub fn level(&self) -> u32 {
+        self.path.len() as u32 - 1
+    }
+
+    /// Looks at the given `side` of the current internal node, moving to it if
+    /// it is an internal node.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, Step};
+    /// let mut trie = BinTrie::new();
+    /// trie.insert(5, |_| true, |_, _| true);
+    /// let mut cursor = trie.cursor();
+    /// assert_eq!(cursor.descend(false), Step::Empty);
+    /// assert_eq!(cursor.descend(true), Step::Leaf(5));
+    /// assert_eq!(cursor.level(), 0);
+    /// ```
+    pub fn descend(&mut self, side: bool) -> Step {
+        let index = *self.path.last().unwrap();
+        match self.trie.internals[index as usize].0[if side { 1 } else { 0 }] {
+            0 => Step::Empty,
+            n if n & HIGH != 0 => Step::Leaf(self.trie.untag(n)),
+            n => {
+                self.path.push(n);
+                Step::Internal
+            }
+        }
+    }
+
+    /// Moves back up to the parent of the current internal node.
+    ///
+    /// Returns `false` without moving if the cursor is at the root.
+    pub fn ascend(&mut self) -> bool {
+        if self.path.len() == 1 {
+            return false;
+        }
+        self.path.pop();
+        true
+    }
+
+    /// Moves back to the root.
+    pub fn reset(&mut self) {
+        self.path.truncate(1);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{Inserted, Internal, HIGH};
+use alloc::{vec, vec::Vec};
+use core::slice;
+
+/// A `BinTrie` whose maximum depth is fixed at compile time.
+///
+/// Every traversal loops over `0..DEPTH`, so the compiler knows how many levels
+/// there are and can unroll the loops for small depths. Otherwise it behaves
+/// like `BinTrie::new_depth(DEPTH)`, which should be used when the depth is only
+/// known at runtime.
+///
+/// `cargo bench --bench const_depth` compares `get` against `BinTrie` with a
+/// depth of `8`, all `256` items present and random keys.
+#[derive(Clone, Debug)]
+pub struct BinTrieConst<const DEPTH: u32> {
+    /// The root node is always at index `0`.
+    internals: Vec<Internal>,
+    /// The number of items in the trie.
+    len: usize,
+}
+
+impl<const DEPTH: u32> BinTrieConst<DEPTH> {
+    /// Makes a new trie.
+    ///
+    /// Panics if `DEPTH` is `0`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieConst;
+    /// let trie = BinTrieConst::<8>::new();
+    /// ```
+    pub fn new() -> Self {
+        assert!(DEPTH > 0);
+        Self {
+            internals: vec![Internal::default()],
+            len: 0,
+        }
+    }
+
+    /// The maximum depth of the trie, which is always `DEPTH`.
+    pub fn depth(&self) -> u32 {
+        DEPTH
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a number that does not have the most significant bit set.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrieConst, Inserted};
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = BinTrieConst::<8>::new();
+    /// for n in 0..256 {
+    ///     assert_eq!(trie.insert(n, |l| lookup(n, l), lookup), Inserted::New);
+    /// }
+    /// assert_eq!(
+    ///     trie.insert(256, |l| lookup(256, l), lookup),
+    ///     Inserted::DepthExhausted { replaced: 0 }
+    /// );
+    /// assert_eq!(trie.len(), 256);
+    /// ```
+    pub fn insert<K, F>(&mut self, item: u32, mut key: K, mut lookup: F) -> Inserted
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u32, u32) -> bool,
+    {
+        // Always check that the high bit is not set in the item.
+        assert!(item & HIGH == 0);
+        let mut index = 0;
+        for i in 0..DEPTH - 1 {
+            let position = if key(i) { 1 } else { 0 };
+            match self.internals[index].0[position] {
+                // Empty node encountered.
+                0 => {
+                    self.internals[index].0[position] = item | HIGH;
+                    self.len += 1;
+                    return Inserted::New;
+                }
+                // The item itself is already present.
+                m if m == item | HIGH => return Inserted::AlreadyPresent,
+                // Leaf node encountered.
+                m if m & HIGH != 0 => {
+                    // Move the existing leaf into a new internal node.
+                    let mut new_internal = Internal::default();
+                    new_internal.0[if lookup(m & !HIGH, i + 1) { 1 } else { 0 }] = m;
+                    let new_index = self.internals.len() as u32;
+                    // Panic if the indices run out.
+                    assert!(new_index & HIGH == 0);
+                    self.internals.push(new_internal);
+                    self.internals[index].0[position] = new_index;
+                    index = new_index as usize;
+                }
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+
+        // For the last bit we replace whatever occupies the space.
+        let position = if key(DEPTH - 1) { 1 } else { 0 };
+        let spot = &mut self.internals[index].0[position];
+        let old = *spot;
+        *spot = item | HIGH;
+        match old {
+            0 => {
+                self.len += 1;
+                Inserted::New
+            }
+            m if m == item | HIGH => Inserted::AlreadyPresent,
+            m => Inserted::DepthExhausted {
+                replaced: m & !HIGH,
+            },
+        }
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieConst;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = BinTrieConst::<8>::new();
+    /// for n in (0..8).step_by(2) {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert_eq!(trie.get(|l| lookup(4, l)), Some(4));
+    /// assert_eq!(trie.get(|l| lookup(3, l)), None);
+    /// ```
+    #[inline]
+    pub fn get<K>(&self, mut key: K) -> Option<u32>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        let mut index = 0;
+        for i in 0..DEPTH {
+            let position = if key(i) { 1 } else { 0 };
+            match self.internals[index].0[position] {
+                // Empty node encountered.
+                0 => return None,
+                // Leaf node encountered.
+                m if m & HIGH != 0 => return Some(m & !HIGH),
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+        None
+    }
+
+    /// Get an iterator over the items added to the trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieConst;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = BinTrieConst::<4>::new();
+    /// for n in 0..4 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![0, 2, 1, 3]);
+    /// ```
+    pub fn items(&self) -> impl Iterator<Item = u32> + '_ {
+        IterConst {
+            trie: self,
+            indices: vec![self.internals[0].0.iter()],
+        }
+    }
+}
+
+impl<const DEPTH: u32> Default for BinTrieConst<DEPTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct IterConst<'a, const DEPTH: u32> {
+    trie: &'a BinTrieConst<DEPTH>,
+    indices: Vec<slice::Iter<'a, u32>>,
+}
+
+impl<'a, const DEPTH: u32> Iterator for IterConst<'a, DEPTH> {
+    type Item = u32;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut current = self.indices.pop()?;
+            let n = if let Some(n) = current.next() {
+                self.indices.push(current);
+                n
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => return Some(n & !HIGH),
+                // Internal node
+                &n => self.indices.push(self.trie.internals[n as usize].0.iter()),
+            }
+        }
+    }
+}
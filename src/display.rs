@@ -0,0 +1,73 @@
+use crate::{BinTrie, HIGH};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// Renders the trie as an indented tree of plain text.
+///
+/// Every occupied slot is written on its own line, indented under its internal
+/// node and prefixed with its side in brackets, where `0` is `false` and `1` is
+/// `true`. Leaves show their item and internal nodes show `*`. Empty slots are
+/// left out.
+///
+/// The precision limits how many levels are written, so `{:.3}` stops after
+/// three levels and writes `...` under each internal node that was cut off.
+/// Without a precision every level is written, which can be a lot of output
+/// for a large trie.
+///
+/// ```
+/// # use bintrie::BinTrie;
+/// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+/// let mut trie = BinTrie::new();
+/// for &n in &[0, 1, 2] {
+///     trie.insert(n, |l| lookup(n, l), lookup);
+/// }
+/// assert_eq!(trie.to_string(), "*\n  [0] *\n    [0] 0\n    [1] 2\n  [1] 1\n");
+/// assert_eq!(format!("{:.1}", trie), "*\n  [0] *\n    ...\n  [1] 1\n");
+/// assert_eq!(format!("{:.0}", trie), "*\n  ...\n");
+/// ```
+impl fmt::Display for BinTrie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max_level = f.precision().unwrap_or(usize::MAX);
+        writeln!(f, "*")?;
+        // The internal node or leaf in each slot along with its side and level.
+        let mut stack = vec![];
+        let push_children = |stack: &mut Vec<(u32, usize, usize)>, index: u32, level| {
+            // Push the `true` side first so that the `false` side comes first.
+            for side in (0..2).rev() {
+                match self.internals[index as usize].0[side] {
+                    // Empty node
+                    0 => {}
+                    n => stack.push((n, side, level)),
+                }
+            }
+        };
+        if max_level > 0 {
+            push_children(&mut stack, 0, 1);
+        } else if self.internals[0].0 != [0, 0] {
+            writeln!(f, "  ...")?;
+        }
+        while let Some((n, side, level)) = stack.pop() {
+            let indent = 2 * level;
+            if n & HIGH != 0 {
+                // Leaf node
+                writeln!(
+                    f,
+                    "{:indent$}[{}] {}",
+                    "",
+                    side,
+                    self.untag(n),
+                    indent = indent
+                )?;
+            } else {
+                // Internal node
+                writeln!(f, "{:indent$}[{}] *", "", side, indent = indent)?;
+                if level < max_level {
+                    push_children(&mut stack, n, level + 1);
+                } else {
+                    writeln!(f, "{:indent$}...", "", indent = indent + 2)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{BinTrie, HIGH};
+use alloc::{string::String, vec};
+use core::fmt::Write;
+
+impl BinTrie {
+    /// Renders the trie as a Graphviz DOT graph.
+    ///
+    /// Internal nodes are boxes labeled with their index and leaves are ellipses
+    /// labeled with their item. Every edge is labeled with the side it takes,
+    /// where `0` is `false` and `1` is `true`. Empty slots are left out.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let trie: BinTrie = vec![0, 2].into_iter().collect();
+    /// let dot = trie.to_dot();
+    /// assert!(dot.starts_with("digraph bintrie {"));
+    /// assert!(dot.contains("n0 -> n1 [label=\"0\"];"));
+    /// assert!(dot.contains("l1_1 [shape=ellipse, label=\"2\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph bintrie {\n");
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            // Writing to a `String` never fails.
+            writeln!(dot, "    n{} [shape=box, label=\"{}\"];", index, index).unwrap();
+            for (side, &n) in self.internals[index as usize].0.iter().enumerate() {
+                match n {
+                    // Empty node
+                    0 => {}
+                    // Leaf node
+                    n if n & HIGH != 0 => {
+                        writeln!(
+                            dot,
+                            "    l{}_{} [shape=ellipse, label=\"{}\"];",
+                            index,
+                            side,
+                            self.untag(n)
+                        )
+                        .unwrap();
+                        writeln!(
+                            dot,
+                            "    n{} -> l{}_{} [label=\"{}\"];",
+                            index, index, side, side
+                        )
+                        .unwrap();
+                    }
+                    // Internal node
+                    n => {
+                        writeln!(dot, "    n{} -> n{} [label=\"{}\"];", index, n, side).unwrap();
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
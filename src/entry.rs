@@ -0,0 +1,113 @@
+use crate::{BinTrie, HIGH};
+
+/// The result of walking a key down the trie, created by `BinTrie::entry`.
+#[derive(Debug)]
+pub enum Entry<'a> {
+    /// A leaf was found along the key's path, containing this item.
+    Occupied(u32),
+    /// The key's path ended at an empty slot.
+    Vacant(VacantEntry<'a>),
+}
+
+impl<'a> Entry<'a> {
+    /// Gets the item in the entry, inserting `item` if it is vacant.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie = BinTrie::new();
+    /// assert_eq!(trie.entry(|_| false).or_insert(5), 5);
+    /// assert_eq!(trie.entry(|_| false).or_insert(6), 5);
+    /// ```
+    pub fn or_insert(self, item: u32) -> u32 {
+        match self {
+            Entry::Occupied(item) => item,
+            Entry::Vacant(entry) => {
+                entry.insert(item);
+                item
+            }
+        }
+    }
+}
+
+/// An empty slot found by `BinTrie::entry`.
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    trie: &'a mut BinTrie,
+    /// The internal node containing the empty slot.
+    index: usize,
+    /// The position of the empty slot in the internal node.
+    position: usize,
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Places a number that does not have the trie's tag bit (see `BinTrie::tag_bit`) set in the slot.
+    ///
+    /// This does not walk the trie again.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, Entry};
+    /// let mut trie = BinTrie::new();
+    /// if let Entry::Vacant(entry) = trie.entry(|_| true) {
+    ///     entry.insert(3);
+    /// }
+    /// assert_eq!(trie.get(|_| true), Some(3));
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn insert(self, item: u32) {
+        // Always check that the tag bit is not set in the item.
+        assert!(item & 1 << self.trie.tag_bit == 0);
+        self.trie.record(self.index);
+        self.trie.internals[self.index].0[self.position] = self.trie.tag(item);
+        self.trie.len += 1;
+    }
+}
+
+impl BinTrie {
+    /// Walks the key down the trie once, returning the entry it ends at.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// If a leaf is found anywhere along the key's path, the entry is occupied by
+    /// it, even if it sits above where another item with this key would be
+    /// placed. Otherwise the path ends at an empty slot, which is remembered so
+    /// that `VacantEntry::insert` can fill it without walking the trie again.
+    /// Since an item is only ever placed in the empty slot, no existing leaf needs
+    /// to be moved, so no lookup function is required.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, Entry};
+    /// let mut trie = BinTrie::new();
+    /// match trie.entry(|_| false) {
+    ///     Entry::Occupied(_) => unreachable!(),
+    ///     Entry::Vacant(entry) => entry.insert(5),
+    /// }
+    /// match trie.entry(|_| false) {
+    ///     Entry::Occupied(item) => assert_eq!(item, 5),
+    ///     Entry::Vacant(_) => unreachable!(),
+    /// }
+    /// ```
+    pub fn entry<K>(&mut self, mut key: K) -> Entry<'_>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        let mut index = 0;
+        for i in 0..self.depth {
+            let position = if key(i) { 1 } else { 0 };
+            match self.internals[index].0[position] {
+                // Empty node encountered.
+                0 => {
+                    return Entry::Vacant(VacantEntry {
+                        trie: self,
+                        index,
+                        position,
+                    })
+                }
+                // Leaf node encountered.
+                m if m & HIGH != 0 => return Entry::Occupied(self.untag(m)),
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+        unreachable!("the last level of the trie only contains leaves")
+    }
+}
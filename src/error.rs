@@ -0,0 +1,58 @@
+use core::fmt;
+
+/// An error produced when modifying a `BinTrie` fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinTrieError {
+    /// The item has the trie's tag bit (see `BinTrie::tag_bit`) set, which is reserved to tag leaves.
+    InvalidItem,
+    /// The trie would need more internal nodes than its indices can address.
+    CapacityExceeded,
+    /// A different item is in the slot at the maximum depth and the
+    /// `CollisionPolicy` is `Reject`.
+    Collision,
+}
+
+impl fmt::Display for BinTrieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BinTrieError::InvalidItem => write!(f, "item has the trie's tag bit set"),
+            BinTrieError::CapacityExceeded => {
+                write!(f, "trie has run out of internal node indices")
+            }
+            BinTrieError::Collision => {
+                write!(
+                    f,
+                    "item collides with a different item at the maximum depth"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BinTrieError {}
+
+/// An error produced when a key provides a child index that is out of range.
+///
+/// Returned by `BinTrieN::try_get` when the key gives an index that is not less
+/// than the fan-out of the trie.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyError {
+    /// The level where the key gave the index.
+    pub level: u32,
+    /// The index the key gave.
+    pub value: usize,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "key gave child index {} at level {}, which is out of range",
+            self.value, self.level
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyError {}
@@ -0,0 +1,278 @@
+use crate::{Inserted, KeyError, HIGH};
+use alloc::{vec, vec::Vec};
+use core::slice;
+
+/// Contains a list of `FANOUT` children node IDs.
+///
+/// This is the same as the `Internal` node of `BinTrie`, but with `FANOUT` slots.
+#[derive(Copy, Clone, Debug)]
+struct InternalN<const FANOUT: usize>([u32; FANOUT]);
+
+impl<const FANOUT: usize> Default for InternalN<FANOUT> {
+    fn default() -> Self {
+        Self([0; FANOUT])
+    }
+}
+
+/// A trie where every internal node has `FANOUT` children instead of `2`.
+///
+/// The key and lookup functions provide the index of the child to take at every
+/// level, which must be less than `FANOUT`. A larger fan-out makes the trie
+/// shallower at the cost of larger nodes, since every internal node takes
+/// `4 * FANOUT` bytes. For example, `BinTrieN<8>` consumes `3` bits of the key
+/// per level and `BinTrieN<16>` consumes `4`.
+///
+/// `BinTrie` remains the specialization for `2` children, which can use `bool`
+/// keys and skip the bounds checks on the child index.
+#[derive(Clone, Debug)]
+pub struct BinTrieN<const FANOUT: usize> {
+    /// The root node is always at index `0`.
+    internals: Vec<InternalN<FANOUT>>,
+    /// The maximum depth to stop at.
+    depth: u32,
+    /// The number of items in the trie.
+    len: usize,
+}
+
+impl<const FANOUT: usize> BinTrieN<FANOUT> {
+    /// Makes a new trie with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieN;
+    /// let trie = BinTrieN::<8>::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new trie with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieN;
+    /// let trie = BinTrieN::<8>::new_depth(11);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        assert!(FANOUT > 0);
+        assert!(depth > 0);
+        Self {
+            internals: vec![InternalN::default()],
+            depth,
+            len: 0,
+        }
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a number that does not have the most significant bit set.
+    ///
+    /// `K(n)` - A function that provides the child index at level `n` for the key.
+    /// `F(item, n)` - A function that must be able to look up the child index
+    ///    at level `n` from a previously inserted item.
+    ///
+    /// Panics if a child index is not less than `FANOUT`.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrieN, Inserted};
+    /// let lookup = |n: u32, l: u32| ((n >> (3 * l)) & 7) as usize;
+    /// let mut trie = BinTrieN::<8>::new_depth(11);
+    /// for n in 0..100 {
+    ///     assert_eq!(trie.insert(n, |l| lookup(n, l), lookup), Inserted::New);
+    /// }
+    /// assert_eq!(trie.len(), 100);
+    /// ```
+    pub fn insert<K, F>(&mut self, item: u32, mut key: K, mut lookup: F) -> Inserted
+    where
+        K: FnMut(u32) -> usize,
+        F: FnMut(u32, u32) -> usize,
+    {
+        // Always check that the high bit is not set in the item.
+        assert!(item & HIGH == 0);
+        let mut index = 0;
+        for i in 0..self.depth - 1 {
+            let position = key(i);
+            match self.internals[index].0[position] {
+                // Empty node encountered.
+                0 => {
+                    self.internals[index].0[position] = item | HIGH;
+                    self.len += 1;
+                    return Inserted::New;
+                }
+                // The item itself is already present.
+                m if m == item | HIGH => return Inserted::AlreadyPresent,
+                // Leaf node encountered.
+                m if m & HIGH != 0 => {
+                    // Move the existing leaf into a new internal node.
+                    let mut new_internal = InternalN::default();
+                    new_internal.0[lookup(m & !HIGH, i + 1)] = m;
+                    let new_index = self.internals.len() as u32;
+                    // Panic if the indices run out.
+                    assert!(new_index & HIGH == 0);
+                    self.internals.push(new_internal);
+                    self.internals[index].0[position] = new_index;
+                    index = new_index as usize;
+                }
+                // Internal node encountered.
+                m => {
+                    index = m as usize;
+                }
+            }
+        }
+
+        // For the last level we replace whatever occupies the space.
+        let position = key(self.depth - 1);
+        let spot = &mut self.internals[index].0[position];
+        let old = *spot;
+        *spot = item | HIGH;
+        match old {
+            0 => {
+                self.len += 1;
+                Inserted::New
+            }
+            m if m == item | HIGH => Inserted::AlreadyPresent,
+            m => Inserted::DepthExhausted {
+                replaced: m & !HIGH,
+            },
+        }
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the child index at level `n` for the key.
+    ///
+    /// Panics if a child index is not less than `FANOUT`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieN;
+    /// let lookup = |n: u32, l: u32| ((n >> (3 * l)) & 7) as usize;
+    /// let mut trie = BinTrieN::<8>::new_depth(11);
+    /// for n in 0..7 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert_eq!(trie.get(|l| lookup(3, l)), Some(3));
+    /// assert_eq!(trie.get(|l| lookup(7, l)), None);
+    /// ```
+    pub fn get<K>(&self, mut key: K) -> Option<u32>
+    where
+        K: FnMut(u32) -> usize,
+    {
+        let mut index = 0;
+        for i in 0..self.depth {
+            match self.internals[index].0[key(i)] {
+                // Empty node encountered.
+                0 => return None,
+                // Leaf node encountered.
+                m if m & HIGH != 0 => return Some(m & !HIGH),
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+        None
+    }
+
+    /// Perform a lookup, returning an error if the key gives an index out of range.
+    ///
+    /// `K(n)` - A function that provides the child index at level `n` for the key.
+    ///
+    /// This is like `get`, but returns a `KeyError` with the level and the index
+    /// instead of panicking if a child index is not less than `FANOUT`, so a
+    /// malformed key cannot bring down the process.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrieN, KeyError};
+    /// let lookup = |n: u32, l: u32| ((n >> (3 * l)) & 7) as usize;
+    /// let mut trie = BinTrieN::<8>::new_depth(11);
+    /// for n in 0..64 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert_eq!(trie.try_get(|l| lookup(9, l)), Ok(Some(9)));
+    /// assert_eq!(
+    ///     trie.try_get(|l| if l == 0 { 1 } else { 8 }),
+    ///     Err(KeyError { level: 1, value: 8 })
+    /// );
+    /// ```
+    pub fn try_get<K>(&self, mut key: K) -> Result<Option<u32>, KeyError>
+    where
+        K: FnMut(u32) -> usize,
+    {
+        let mut index = 0;
+        for i in 0..self.depth {
+            let position = key(i);
+            let slot = self.internals[index].0.get(position).ok_or(KeyError {
+                level: i,
+                value: position,
+            })?;
+            match *slot {
+                // Empty node encountered.
+                0 => return Ok(None),
+                // Leaf node encountered.
+                m if m & HIGH != 0 => return Ok(Some(m & !HIGH)),
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get an iterator over the items added to the trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieN;
+    /// let lookup = |n: u32, l: u32| ((n >> (3 * l)) & 7) as usize;
+    /// let mut trie = BinTrieN::<8>::new_depth(11);
+    /// for n in 0..10 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// let mut items = trie.items().collect::<Vec<u32>>();
+    /// items.sort();
+    /// assert_eq!(items, (0..10).collect::<Vec<u32>>());
+    /// ```
+    pub fn items<'a>(&'a self) -> impl Iterator<Item = u32> + 'a {
+        IterN {
+            trie: self,
+            indices: vec![self.internals[0].0.iter()],
+        }
+    }
+}
+
+impl<const FANOUT: usize> Default for BinTrieN<FANOUT> {
+    fn default() -> Self {
+        Self::new_depth(8192)
+    }
+}
+
+struct IterN<'a, const FANOUT: usize> {
+    trie: &'a BinTrieN<FANOUT>,
+    indices: Vec<slice::Iter<'a, u32>>,
+}
+
+impl<'a, const FANOUT: usize> Iterator for IterN<'a, FANOUT> {
+    type Item = u32;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut current = self.indices.pop()?;
+            let n = if let Some(n) = current.next() {
+                self.indices.push(current);
+                n
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => return Some(n & !HIGH),
+                // Internal node
+                &n => self.indices.push(self.trie.internals[n as usize].0.iter()),
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{BinTrie, Heuristic, IntoHeuristic, HIGH};
+use alloc::{vec, vec::Vec};
+use core::iter::FusedIterator;
+
+impl BinTrie {
+    /// Iterates over the trie like `explore`, also filtering with a `predicate`.
+    ///
+    /// `P(side, leaf)` - A function that decides whether to keep an occupied slot,
+    ///    given the side it is on and the item if the slot holds a leaf.
+    ///
+    /// At every internal node, the `heuristic` picks the sides to visit first.
+    /// Empty slots are skipped, and the predicate is then called for every
+    /// occupied slot on a side the heuristic picked. For a leaf it is passed
+    /// `Some(item)` and decides whether the item is yielded. For an internal node
+    /// it is passed `None` and decides whether to descend into it, so whole
+    /// subtrees can be rejected without visiting their leaves.
+    ///
+    /// This is the same as `explore(heuristic).filter(...)` when the predicate
+    /// accepts every internal node.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, FilterHeuristic};
+    /// let trie: BinTrie = (0..16).collect();
+    /// let all = FilterHeuristic(|_| true);
+    /// // Keep the even items.
+    /// let mut even = trie
+    ///     .explore_filter(all.clone(), |_, leaf| leaf.map_or(true, |n| n % 2 == 0))
+    ///     .collect::<Vec<u32>>();
+    /// even.sort();
+    /// assert_eq!(even, vec![0, 2, 4, 6, 8, 10, 12, 14]);
+    /// // Never take a `true` side, which only leaves the item with no bits set.
+    /// let zero = trie.explore_filter(all, |side, _| !side).collect::<Vec<u32>>();
+    /// assert_eq!(zero, vec![0]);
+    /// ```
+    pub fn explore_filter<'a, H, P>(
+        &'a self,
+        heuristic: H,
+        predicate: P,
+    ) -> impl FusedIterator<Item = u32> + 'a
+    where
+        H: IntoHeuristic,
+        H::Heuristic: 'a,
+        P: FnMut(bool, Option<u32>) -> bool + 'a,
+    {
+        let heuristic = heuristic.into_heuristic();
+        let iter = heuristic.iter();
+        ExploreFilterIter {
+            trie: self,
+            predicate,
+            indices: vec![(&self.internals[0].0, heuristic, iter)],
+        }
+    }
+}
+
+struct ExploreFilterIter<'a, H, P>
+where
+    H: Heuristic,
+{
+    trie: &'a BinTrie,
+    predicate: P,
+    indices: Vec<(&'a [u32; 2], H, H::Iter)>,
+}
+
+impl<'a, H, P> Iterator for ExploreFilterIter<'a, H, P>
+where
+    H: Heuristic,
+    P: FnMut(bool, Option<u32>) -> bool,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (array, heuristic, mut iter) = self.indices.pop()?;
+            let (choice, n) = if let Some(choice) = iter.next() {
+                let n = array[if choice { 1 } else { 0 }];
+                self.indices.push((array, heuristic.clone(), iter));
+                (choice, n)
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => {
+                    let item = self.trie.untag(n);
+                    if (self.predicate)(choice, Some(item)) {
+                        return Some(item);
+                    }
+                }
+                // Internal node
+                n => {
+                    if (self.predicate)(choice, None) {
+                        let mut heuristic = heuristic;
+                        heuristic.enter(choice);
+                        let iter = heuristic.iter();
+                        self.indices
+                            .push((&self.trie.internals[n as usize].0, heuristic, iter));
+                    }
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.indices.is_empty() {
+            (0, Some(0))
+        } else {
+            (0, Some(self.trie.len))
+        }
+    }
+}
+
+/// Once the stack is empty nothing can be pushed to it again.
+impl<'a, H, P> FusedIterator for ExploreFilterIter<'a, H, P>
+where
+    H: Heuristic,
+    P: FnMut(bool, Option<u32>) -> bool,
+{
+}
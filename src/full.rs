@@ -0,0 +1,242 @@
+use crate::Inserted;
+use alloc::{vec, vec::Vec};
+
+/// Contains a list of 2 children node IDs along with the kind of each child.
+///
+/// Bit `i` of `leaves` is set if slot `i` holds a leaf, in which case the slot
+/// may hold any `u32`. Otherwise the slot is either `0` if it is empty or the
+/// index of an internal node.
+///
+/// The mask makes the node `12` bytes rather than `8`, since the alignment of
+/// the slots pads it out. Nodes are no longer a power of two in size, so some of
+/// them straddle two cache lines, and `5` of them fit in a cache line instead of
+/// `8`.
+#[derive(Copy, Clone, Debug, Default)]
+struct InternalFull {
+    slots: [u32; 2],
+    leaves: u8,
+}
+
+impl InternalFull {
+    fn is_leaf(&self, position: usize) -> bool {
+        self.leaves & (1 << position) != 0
+    }
+
+    fn set_leaf(&mut self, position: usize, item: u32) {
+        self.slots[position] = item;
+        self.leaves |= 1 << position;
+    }
+
+    fn set_internal(&mut self, position: usize, index: u32) {
+        self.slots[position] = index;
+        self.leaves &= !(1 << position);
+    }
+}
+
+/// A `BinTrie` which can store every `u32`, including those with the most
+/// significant bit set.
+///
+/// `BinTrie` tags leaves with the most significant bit, which limits items to
+/// `31` bits. This type keeps a mask in every internal node to tell leaves apart
+/// instead, at the cost of `50%` more memory per internal node. See
+/// `BinTrieMap` for another way to store full `u32` items, which keeps the
+/// smaller nodes but adds an indirection.
+#[derive(Clone, Debug)]
+pub struct BinTrieFull {
+    /// The root node is always at index `0`.
+    internals: Vec<InternalFull>,
+    /// The maximum depth to stop at.
+    depth: u32,
+    /// The number of items in the trie.
+    len: usize,
+}
+
+impl BinTrieFull {
+    /// Makes a new trie with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieFull;
+    /// let trie = BinTrieFull::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new trie with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieFull;
+    /// let trie = BinTrieFull::new_depth(128);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        assert!(depth > 0);
+        Self {
+            internals: vec![InternalFull::default()],
+            depth,
+            len: 0,
+        }
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts any number.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// Returns whether the item was new, already present, or replaced a
+    /// different item because the maximum depth was exhausted.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrieFull, Inserted};
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = BinTrieFull::new_depth(32);
+    /// for &n in &[0, 1, u32::MAX, 1 << 31] {
+    ///     assert_eq!(trie.insert(n, |l| lookup(n, l), lookup), Inserted::New);
+    /// }
+    /// assert_eq!(trie.insert(0, |l| lookup(0, l), lookup), Inserted::AlreadyPresent);
+    /// assert_eq!(trie.len(), 4);
+    /// ```
+    pub fn insert<K, F>(&mut self, item: u32, mut key: K, mut lookup: F) -> Inserted
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u32, u32) -> bool,
+    {
+        let mut index = 0;
+        for i in 0..self.depth - 1 {
+            let position = if key(i) { 1 } else { 0 };
+            let node = self.internals[index];
+            let m = node.slots[position];
+            if node.is_leaf(position) {
+                // The item itself is already present.
+                if m == item {
+                    return Inserted::AlreadyPresent;
+                }
+                // Move the existing leaf into a new internal node.
+                let mut new_internal = InternalFull::default();
+                new_internal.set_leaf(if lookup(m, i + 1) { 1 } else { 0 }, m);
+                let new_index = self.internals.len() as u32;
+                // Panic if the indices run out.
+                assert!(new_index != 0);
+                self.internals.push(new_internal);
+                self.internals[index].set_internal(position, new_index);
+                index = new_index as usize;
+            } else if m == 0 {
+                // Empty node encountered.
+                self.internals[index].set_leaf(position, item);
+                self.len += 1;
+                return Inserted::New;
+            } else {
+                // Internal node encountered.
+                index = m as usize;
+            }
+        }
+
+        // For the last bit we replace whatever occupies the space.
+        let position = if key(self.depth - 1) { 1 } else { 0 };
+        let node = &mut self.internals[index];
+        let was_leaf = node.is_leaf(position);
+        let old = node.slots[position];
+        node.set_leaf(position, item);
+        if !was_leaf {
+            self.len += 1;
+            Inserted::New
+        } else if old == item {
+            Inserted::AlreadyPresent
+        } else {
+            Inserted::DepthExhausted { replaced: old }
+        }
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieFull;
+    /// let mut trie = BinTrieFull::new();
+    /// trie.insert(u32::MAX, |_| true, |_, _| true);
+    /// assert_eq!(trie.get(|_| true), Some(u32::MAX));
+    /// assert_eq!(trie.get(|_| false), None);
+    /// ```
+    pub fn get<K>(&self, mut key: K) -> Option<u32>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        let mut index = 0;
+        for i in 0..self.depth {
+            let position = if key(i) { 1 } else { 0 };
+            let node = &self.internals[index];
+            match node.slots[position] {
+                // Leaf node encountered.
+                m if node.is_leaf(position) => return Some(m),
+                // Empty node encountered.
+                0 => return None,
+                // Internal node encountered.
+                m => index = m as usize,
+            }
+        }
+        None
+    }
+
+    /// Get an iterator over the items added to the trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieFull;
+    /// let mut trie = BinTrieFull::new();
+    /// trie.insert(0, |_| false, |_, _| false);
+    /// trie.insert(u32::MAX, |_| true, |_, _| true);
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![0, u32::MAX]);
+    /// ```
+    pub fn items(&self) -> impl Iterator<Item = u32> + '_ {
+        IterFull {
+            trie: self,
+            indices: vec![(0, 0)],
+        }
+    }
+}
+
+impl Default for BinTrieFull {
+    fn default() -> Self {
+        Self::new_depth(8192)
+    }
+}
+
+struct IterFull<'a> {
+    trie: &'a BinTrieFull,
+    /// The internal nodes being iterated and the next slot to look at in each.
+    indices: Vec<(usize, usize)>,
+}
+
+impl<'a> Iterator for IterFull<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, position) = self.indices.pop()?;
+            if position == 2 {
+                continue;
+            }
+            // Push the node back, moving on to the next slot.
+            self.indices.push((index, position + 1));
+            let node = &self.trie.internals[index];
+            match node.slots[position] {
+                // Leaf node
+                m if node.is_leaf(position) => return Some(m),
+                // Empty node
+                0 => {}
+                // Internal node
+                m => self.indices.push((m as usize, 0)),
+            }
+        }
+    }
+}
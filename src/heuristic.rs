@@ -1,3 +1,6 @@
+use alloc::rc::Rc;
+use core::cell::Cell;
+
 /// The `Heuristic` chooses which side to explore next.
 ///
 /// This is not useful for finding perfect nearest neighbors because
@@ -16,7 +19,77 @@ pub trait Heuristic: Clone {
     /// This is passed the `side`.
     fn enter(&mut self, side: bool);
 
-    /// Must return an iterator which returns values below `16`, otherwise panics.
+    /// Must return an iterator over the sides to enter, in the order to enter them.
+    fn iter(&self) -> Self::Iter;
+
+    /// Only allows a side if both `self` and `other` allow it.
+    ///
+    /// The sides are explored in the order given by `self`.
+    ///
+    /// ```
+    /// # use bintrie::{Heuristic, FilterHeuristic};
+    /// let both = FilterHeuristic(|_| true).and(FilterHeuristic(|n: bool| n));
+    /// assert_eq!(both.iter().collect::<Vec<bool>>(), vec![true]);
+    /// ```
+    fn and<H>(self, other: H) -> AndHeuristic<Self, H>
+    where
+        Self: Sized,
+        H: Heuristic,
+    {
+        AndHeuristic(self, other)
+    }
+
+    /// Allows a side if either `self` or `other` allows it.
+    ///
+    /// The sides allowed by `self` are explored first.
+    ///
+    /// ```
+    /// # use bintrie::{Heuristic, FilterHeuristic};
+    /// let either = FilterHeuristic(|n: bool| n).or(FilterHeuristic(|n: bool| !n));
+    /// assert_eq!(either.iter().collect::<Vec<bool>>(), vec![true, false]);
+    /// ```
+    fn or<H>(self, other: H) -> OrHeuristic<Self, H>
+    where
+        Self: Sized,
+        H: Heuristic,
+    {
+        OrHeuristic(self, other)
+    }
+
+    /// Only allows the sides that `self` does not allow.
+    ///
+    /// ```
+    /// # use bintrie::{Heuristic, FilterHeuristic};
+    /// let inverse = FilterHeuristic(|n: bool| n).not();
+    /// assert_eq!(inverse.iter().collect::<Vec<bool>>(), vec![false]);
+    /// ```
+    fn not(self) -> NotHeuristic<Self>
+    where
+        Self: Sized,
+    {
+        NotHeuristic(self)
+    }
+}
+
+/// A heuristic which is updated in place and undone on the way back up.
+///
+/// Unlike `Heuristic`, this is never cloned. `BinTrie::explore_backtracking`
+/// calls `enter` when it descends into an internal node and `leave` with the
+/// same side once it has explored everything below it, so a single heuristic
+/// follows the exploration up and down the trie. This avoids cloning a large
+/// state at every internal node, at the cost of having to undo every change
+/// exactly: after `enter(side)` and `leave(side)`, `iter` must give the same
+/// sides as before.
+pub trait BacktrackHeuristic {
+    type Iter: Iterator<Item = bool>;
+
+    /// This is passed the `side` being entered.
+    fn enter(&mut self, side: bool);
+
+    /// This is passed the `side` being left, and must undo what `enter` did.
+    fn leave(&mut self, side: bool);
+
+    /// Must return an iterator over the sides to enter, in the order to enter them.
     fn iter(&self) -> Self::Iter;
 }
 
@@ -73,7 +146,7 @@ where
 #[doc(hidden)]
 pub struct FilterHeuristicIter<F> {
     f: F,
-    iter: std::slice::Iter<'static, bool>,
+    iter: core::slice::Iter<'static, bool>,
 }
 
 impl<F> Iterator for FilterHeuristicIter<F>
@@ -111,7 +184,7 @@ impl<F> Heuristic for SearchHeuristic<F>
 where
     F: FnMut(bool) -> bool + Clone,
 {
-    type Iter = std::iter::Cloned<std::slice::Iter<'static, bool>>;
+    type Iter = core::iter::Cloned<core::slice::Iter<'static, bool>>;
 
     #[inline(always)]
     fn enter(&mut self, side: bool) {
@@ -127,3 +200,346 @@ where
         }
     }
 }
+
+/// Allows both sides until a maximum level is reached, then allows none.
+///
+/// The root node is at level `0`, so a `max_level` of `1` only explores the
+/// leaves of the root node.
+///
+/// ```
+/// # use bintrie::{BinTrie, DepthHeuristic};
+/// let mut trie = BinTrie::new_depth(32);
+/// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+/// for n in 0..4 {
+///     trie.insert(n, |l| lookup(n, l), lookup);
+/// }
+/// assert_eq!(trie.explore(DepthHeuristic::new(1)).count(), 0);
+/// assert_eq!(trie.explore(DepthHeuristic::new(2)).count(), 4);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct DepthHeuristic {
+    level: u32,
+    max_level: u32,
+}
+
+impl DepthHeuristic {
+    /// Makes a heuristic which explores everything above `max_level`.
+    pub fn new(max_level: u32) -> Self {
+        Self {
+            level: 0,
+            max_level,
+        }
+    }
+}
+
+impl Heuristic for DepthHeuristic {
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, _: bool) {
+        self.level += 1;
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        let mut sides = SidesIter::empty();
+        if self.level < self.max_level {
+            sides.push(false);
+            sides.push(true);
+        }
+        sides
+    }
+}
+
+impl BacktrackHeuristic for DepthHeuristic {
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, side: bool) {
+        Heuristic::enter(self, side);
+    }
+
+    #[inline(always)]
+    fn leave(&mut self, _: bool) {
+        self.level -= 1;
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        Heuristic::iter(self)
+    }
+}
+
+/// Only allows the side given by the prefix at each level, then allows both sides.
+///
+/// This explores the items whose keys start with `prefix`, where the root node
+/// is at level `0`. An empty prefix explores everything. Leaves found above the
+/// end of the prefix are still yielded, since the trie does not store the rest
+/// of their keys, so check the remaining bits of the key if that matters. See
+/// `BinTrie::explore_prefix`, which also handles prefixes longer than the depth.
+///
+/// ```
+/// # use bintrie::{BinTrie, PrefixHeuristic};
+/// let trie: BinTrie = (0..16).collect();
+/// // Items are their own key, so this finds items whose lowest bits are `0, 1`.
+/// let mut items = trie
+///     .explore(PrefixHeuristic::new(&[false, true]))
+///     .collect::<Vec<u32>>();
+/// items.sort();
+/// assert_eq!(items, vec![2, 6, 10, 14]);
+/// assert_eq!(trie.explore(PrefixHeuristic::new(&[])).count(), 16);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct PrefixHeuristic<'a> {
+    prefix: &'a [bool],
+    level: usize,
+}
+
+impl<'a> PrefixHeuristic<'a> {
+    /// Makes a heuristic which explores the keys starting with `prefix`.
+    pub fn new(prefix: &'a [bool]) -> Self {
+        Self { prefix, level: 0 }
+    }
+}
+
+impl<'a> Heuristic for PrefixHeuristic<'a> {
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, _: bool) {
+        self.level += 1;
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        let mut sides = SidesIter::empty();
+        match self.prefix.get(self.level) {
+            Some(&side) => sides.push(side),
+            None => {
+                sides.push(false);
+                sides.push(true);
+            }
+        }
+        sides
+    }
+}
+
+/// An iterator over the sides chosen by one of the heuristic combinators.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct SidesIter {
+    sides: [bool; 2],
+    start: usize,
+    end: usize,
+}
+
+impl SidesIter {
+    /// Makes an iterator with no sides.
+    pub(crate) fn empty() -> Self {
+        Self {
+            sides: [false; 2],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// Adds a side to the end if it is not already present.
+    pub(crate) fn push(&mut self, side: bool) {
+        if !self.sides[self.start..self.end].contains(&side) {
+            self.sides[self.end] = side;
+            self.end += 1;
+        }
+    }
+
+    /// Checks if the remaining sides contain `side`.
+    pub(crate) fn contains(&self, side: bool) -> bool {
+        self.sides[self.start..self.end].contains(&side)
+    }
+}
+
+impl Iterator for SidesIter {
+    type Item = bool;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            self.start += 1;
+            Some(self.sides[self.start - 1])
+        } else {
+            None
+        }
+    }
+}
+
+/// Collects the distinct sides chosen by a heuristic.
+fn sides<H: Heuristic>(heuristic: &H) -> SidesIter {
+    let mut sides = SidesIter::empty();
+    for side in heuristic.iter() {
+        sides.push(side);
+    }
+    sides
+}
+
+/// Only allows a side if both heuristics allow it. See `Heuristic::and`.
+///
+/// Both heuristics are told about every side that is entered.
+#[derive(Clone)]
+pub struct AndHeuristic<A, B>(pub A, pub B);
+
+impl<A, B> Heuristic for AndHeuristic<A, B>
+where
+    A: Heuristic,
+    B: Heuristic,
+{
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, side: bool) {
+        self.0.enter(side);
+        self.1.enter(side);
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        let other = sides(&self.1);
+        let mut sides = SidesIter::empty();
+        for side in self.0.iter().filter(|&side| other.contains(side)) {
+            sides.push(side);
+        }
+        sides
+    }
+}
+
+/// Allows a side if either heuristic allows it. See `Heuristic::or`.
+///
+/// Both heuristics are told about every side that is entered.
+#[derive(Clone)]
+pub struct OrHeuristic<A, B>(pub A, pub B);
+
+impl<A, B> Heuristic for OrHeuristic<A, B>
+where
+    A: Heuristic,
+    B: Heuristic,
+{
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, side: bool) {
+        self.0.enter(side);
+        self.1.enter(side);
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        let mut sides = sides(&self.0);
+        for side in self.1.iter() {
+            sides.push(side);
+        }
+        sides
+    }
+}
+
+/// Only allows the sides that the inner heuristic does not allow. See `Heuristic::not`.
+#[derive(Clone)]
+pub struct NotHeuristic<H>(pub H);
+
+impl<H> Heuristic for NotHeuristic<H>
+where
+    H: Heuristic,
+{
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, side: bool) {
+        self.0.enter(side);
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        let inner = sides(&self.0);
+        let mut sides = SidesIter::empty();
+        for &side in &[false, true] {
+            if !inner.contains(side) {
+                sides.push(side);
+            }
+        }
+        sides
+    }
+}
+
+/// Explores like the inner heuristic until a budget of internal nodes is used up.
+///
+/// Every internal node that is entered below the root takes one from the
+/// budget. Once it reaches `0`, no more internal nodes are explored, although
+/// the leaves of the internal nodes that were already entered are still yielded,
+/// so exploration winds down instead of stopping abruptly. This bounds the work
+/// done by a search regardless of how the trie is shaped.
+///
+/// Exploration clones the heuristic for every side it enters, so the budget is
+/// kept behind an `Rc<Cell<usize>>` which every clone shares. Otherwise each
+/// branch would get its own copy of the budget and the total would be unbounded.
+/// This also makes the heuristic neither `Send` nor `Sync`.
+///
+/// ```
+/// # use bintrie::{BinTrie, BudgetHeuristic, FilterHeuristic};
+/// let trie: BinTrie = (0..16).collect();
+/// let all = FilterHeuristic(|_| true);
+/// let limited = BudgetHeuristic::new(all.clone(), 3);
+/// // Entering `3` internal nodes along the `false` side reaches the leaves `0` and `8`.
+/// assert_eq!(trie.explore(limited.clone()).collect::<Vec<u32>>(), vec![0, 8]);
+/// assert_eq!(limited.remaining(), 0);
+/// assert_eq!(trie.explore(BudgetHeuristic::new(all, 1000)).count(), 16);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BudgetHeuristic<H> {
+    inner: H,
+    /// The number of internal nodes that may still be entered, shared by every clone.
+    budget: Rc<Cell<usize>>,
+    /// Whether the internal node this heuristic was entered for was over budget.
+    exhausted: bool,
+}
+
+impl<H> BudgetHeuristic<H> {
+    /// Makes a heuristic which explores like `inner`, entering at most `budget` internal nodes.
+    pub fn new(inner: H, budget: usize) -> Self {
+        Self {
+            inner,
+            budget: Rc::new(Cell::new(budget)),
+            exhausted: false,
+        }
+    }
+
+    /// The number of internal nodes that may still be entered.
+    ///
+    /// This is shared by every clone, so it reflects all exploration done with them.
+    pub fn remaining(&self) -> usize {
+        self.budget.get()
+    }
+}
+
+impl<H> Heuristic for BudgetHeuristic<H>
+where
+    H: Heuristic,
+{
+    type Iter = SidesIter;
+
+    #[inline(always)]
+    fn enter(&mut self, side: bool) {
+        match self.budget.get() {
+            0 => self.exhausted = true,
+            budget => {
+                self.budget.set(budget - 1);
+                self.inner.enter(side);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn iter(&self) -> Self::Iter {
+        if self.exhausted {
+            SidesIter::empty()
+        } else {
+            sides(&self.inner)
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{BinTrie, Heuristic, IntoHeuristic, HIGH};
+use alloc::{vec, vec::Vec};
+
+impl BinTrie {
+    /// Iterates over the trie with iterative deepening, using the `heuristic` to prune.
+    ///
+    /// The trie is explored depth-first repeatedly, going one level deeper every
+    /// time, and each pass only yields the leaves found at its deepest level.
+    /// Items are therefore yielded from the shallowest leaves down, and nothing
+    /// past `max_depth` levels is ever visited, so the stack never holds more than
+    /// `max_depth` internal nodes. Exploration ends early once a pass finds no
+    /// internal nodes at its deepest level.
+    ///
+    /// Every pass walks all the levels above it again. On a balanced trie each
+    /// level has about as many nodes as all the levels above it combined, so this
+    /// visits about twice as many nodes as `explore` would with the same limit.
+    /// The heuristic is also cloned and entered again on every pass, so it must
+    /// make the same choices each time for the results to be consistent.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, FilterHeuristic};
+    /// let mut trie = BinTrie::new();
+    /// let lookup = |n: u32, l: u32| match n {
+    ///     3 => false,
+    ///     5 => l == 1,
+    ///     _ => l != 1,
+    /// };
+    /// for &n in &[3, 5, 7] {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// // `7` sits at depth `1`, while `3` and `5` sit at depth `2`.
+    /// let all = FilterHeuristic(|_| true);
+    /// assert_eq!(trie.explore_iddfs(all.clone(), 8).collect::<Vec<u32>>(), vec![7, 3, 5]);
+    /// assert_eq!(trie.explore_iddfs(all, 1).collect::<Vec<u32>>(), vec![7]);
+    /// ```
+    pub fn explore_iddfs<'a, H>(
+        &'a self,
+        heuristic: H,
+        max_depth: u32,
+    ) -> impl Iterator<Item = u32> + 'a
+    where
+        H: IntoHeuristic,
+        H::Heuristic: 'a,
+    {
+        let heuristic = heuristic.into_heuristic();
+        let mut iter = IddfsIter {
+            trie: self,
+            heuristic,
+            limit: 0,
+            max_depth: max_depth.min(self.depth),
+            deeper: true,
+            indices: vec![],
+        };
+        iter.restart();
+        iter
+    }
+}
+
+struct IddfsIter<'a, H>
+where
+    H: Heuristic,
+{
+    trie: &'a BinTrie,
+    /// The heuristic at the root, which every pass starts from.
+    heuristic: H,
+    /// The depth of the leaves yielded by the current pass.
+    limit: u32,
+    max_depth: u32,
+    /// Whether the current pass skipped any internal nodes past its limit.
+    deeper: bool,
+    indices: Vec<(&'a [u32; 2], H, H::Iter)>,
+}
+
+impl<'a, H> IddfsIter<'a, H>
+where
+    H: Heuristic,
+{
+    /// Starts the next pass, returning `false` if there is nothing left to explore.
+    fn restart(&mut self) -> bool {
+        if !self.deeper || self.limit >= self.max_depth {
+            return false;
+        }
+        self.limit += 1;
+        self.deeper = false;
+        let iter = self.heuristic.iter();
+        self.indices
+            .push((&self.trie.internals[0].0, self.heuristic.clone(), iter));
+        true
+    }
+}
+
+impl<'a, H> Iterator for IddfsIter<'a, H>
+where
+    H: Heuristic,
+{
+    type Item = u32;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // The depth of the leaves in the node on top of the stack.
+            let level = self.indices.len() as u32;
+            let (array, heuristic, mut iter) = match self.indices.pop() {
+                Some(current) => current,
+                None if self.restart() => continue,
+                None => return None,
+            };
+            let (choice, n) = if let Some(choice) = iter.next() {
+                let n = array[if choice { 1 } else { 0 }];
+                self.indices.push((array, heuristic.clone(), iter));
+                (choice, n)
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => {
+                    if level == self.limit {
+                        return Some(self.trie.untag(n));
+                    }
+                }
+                // Internal node
+                n => {
+                    if level < self.limit {
+                        let mut heuristic = heuristic;
+                        heuristic.enter(choice);
+                        let iter = heuristic.iter();
+                        self.indices
+                            .push((&self.trie.internals[n as usize].0, heuristic, iter));
+                    } else {
+                        self.deeper = true;
+                    }
+                }
+            }
+        }
+    }
+}
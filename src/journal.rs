@@ -0,0 +1,195 @@
+use crate::{BinTrie, CollisionPolicy, Internal};
+use alloc::vec::Vec;
+
+/// A change to the internal nodes recorded while a `Checkpoint` is active.
+#[derive(Clone, Debug)]
+pub(crate) enum Undo {
+    /// The internal node at this index held these slots before it was written.
+    Node(u32, Internal),
+    /// Every internal node before they were all replaced or truncated.
+    Nodes(Vec<Internal>),
+}
+
+/// The changes recorded while at least one `Checkpoint` is open.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Journal {
+    /// The changes, from the oldest to the most recent.
+    undo: Vec<Undo>,
+    /// The number of checkpoints which are not rolled back or committed yet.
+    open: usize,
+}
+
+impl Journal {
+    /// Marks `checkpoint` as resolved, along with any checkpoints made after it.
+    fn close<'a>(journal: &'a mut Option<Journal>, checkpoint: &Checkpoint) -> &'a mut Journal {
+        let journal = journal
+            .as_mut()
+            .expect("checkpoint was already rolled back or committed");
+        assert!(
+            checkpoint.open < journal.open && checkpoint.journal <= journal.undo.len(),
+            "checkpoint was already rolled back or committed"
+        );
+        journal.open = checkpoint.open;
+        journal
+    }
+}
+
+/// The state of a trie saved by `BinTrie::checkpoint`.
+///
+/// Pass it to `BinTrie::rollback` to undo every change made since, or to
+/// `BinTrie::commit` to keep them.
+#[derive(Debug)]
+#[must_use = "the trie keeps journaling changes until the checkpoint is rolled back or committed"]
+pub struct Checkpoint {
+    /// The number of internal nodes.
+    nodes: usize,
+    /// The maximum depth, which `clone_from` can change.
+    depth: u32,
+    /// The tag bit, which `clone_from` can also change.
+    tag_bit: u32,
+    /// The collision policy, which `clone_from` can also change.
+    collision: CollisionPolicy,
+    /// The number of items.
+    len: usize,
+    /// The free list, which is small compared to the internal nodes.
+    free: Vec<u32>,
+    /// The length of the journal.
+    journal: usize,
+    /// The number of checkpoints that were open, which is `0` for the outermost one.
+    open: usize,
+}
+
+impl BinTrie {
+    /// Saves the current state of the trie so that later changes can be undone.
+    ///
+    /// Until the checkpoint is passed to `rollback` or `commit`, the trie keeps a
+    /// journal with the previous contents of every internal node it writes to.
+    /// Inserting an item writes to at most two existing nodes, since splitting a
+    /// leaf also rewrites the slot of its parent. Methods that replace all of the
+    /// nodes at once, such as `clear` and `compact`, save a copy of every node.
+    ///
+    /// Checkpoints can be nested, as long as the inner ones are rolled back or
+    /// committed first.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie: BinTrie = (0..100).collect();
+    /// let before = trie.clone();
+    /// let checkpoint = trie.checkpoint();
+    /// trie.extend(100..200);
+    /// trie.retain(|n| n % 3 == 0);
+    /// trie.rollback(checkpoint);
+    /// assert_eq!(trie, before);
+    /// assert_eq!(trie.as_raw(), before.as_raw());
+    ///
+    /// // An inner checkpoint taken before anything changed keeps the outer one open.
+    /// let outer = trie.checkpoint();
+    /// let inner = trie.checkpoint();
+    /// trie.extend(100..200);
+    /// trie.commit(inner);
+    /// trie.extend(200..300);
+    /// trie.rollback(outer);
+    /// assert_eq!(trie, before);
+    /// ```
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let journal = self.journal.get_or_insert_with(Journal::default);
+        let open = journal.open;
+        journal.open += 1;
+        Checkpoint {
+            nodes: self.internals.len(),
+            depth: self.depth,
+            tag_bit: self.tag_bit,
+            collision: self.collision,
+            len: self.len,
+            free: self.free.clone(),
+            journal: journal.undo.len(),
+            open,
+        }
+    }
+
+    /// Undoes every change made since the `checkpoint` was made.
+    ///
+    /// Panics if a checkpoint made before this one was already rolled back or committed.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, CollisionPolicy};
+    /// let mut trie: BinTrie = (0..10).collect();
+    /// let outer = trie.checkpoint();
+    /// trie.extend(10..20);
+    /// let inner = trie.checkpoint();
+    /// trie.clear();
+    /// trie.rollback(inner);
+    /// assert_eq!(trie, (0..20).collect());
+    /// trie.rollback(outer);
+    /// assert_eq!(trie, (0..10).collect());
+    ///
+    /// // Replacing the whole trie is undone too, including its tag bit and collision policy.
+    /// let mut trie = BinTrie::new_depth_tag_bit(32, 4);
+    /// trie.insert(1 << 31, |_| true, |_, _| true);
+    /// let checkpoint = trie.checkpoint();
+    /// let mut other = BinTrie::new_depth(32);
+    /// other.set_collision_policy(CollisionPolicy::Reject);
+    /// trie.clone_from(&other);
+    /// trie.rollback(checkpoint);
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![1 << 31]);
+    /// assert_eq!(trie.collision_policy(), CollisionPolicy::KeepLast);
+    /// ```
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        let journal = Journal::close(&mut self.journal, &checkpoint);
+        // Undo the changes from the most recent to the oldest.
+        for undo in journal.undo.drain(checkpoint.journal..).rev() {
+            match undo {
+                Undo::Node(index, internal) => self.internals[index as usize] = internal,
+                Undo::Nodes(internals) => self.internals = internals,
+            }
+        }
+        // Nodes added since the checkpoint are not needed anymore.
+        self.internals.truncate(checkpoint.nodes);
+        self.depth = checkpoint.depth;
+        self.tag_bit = checkpoint.tag_bit;
+        self.collision = checkpoint.collision;
+        self.len = checkpoint.len;
+        self.free = checkpoint.free;
+        if checkpoint.open == 0 {
+            self.journal = None;
+        }
+    }
+
+    /// Keeps every change made since the `checkpoint` was made.
+    ///
+    /// Once the outermost checkpoint is committed, the trie stops journaling.
+    ///
+    /// Panics if a checkpoint made before this one was already rolled back or committed.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie = BinTrie::new();
+    /// let checkpoint = trie.checkpoint();
+    /// trie.extend(0..10);
+    /// trie.commit(checkpoint);
+    /// assert_eq!(trie.len(), 10);
+    /// ```
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        Journal::close(&mut self.journal, &checkpoint);
+        if checkpoint.open == 0 {
+            self.journal = None;
+        }
+    }
+
+    /// Records the internal node at `index` before it is written to.
+    #[inline(always)]
+    pub(crate) fn record(&mut self, index: usize) {
+        if let Some(journal) = &mut self.journal {
+            journal
+                .undo
+                .push(Undo::Node(index as u32, self.internals[index]));
+        }
+    }
+
+    /// Records every internal node before they are all replaced or truncated.
+    pub(crate) fn record_all(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.undo.push(Undo::Nodes(self.internals.clone()));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Functions to key items by their own bits, so that closures are not needed.
+//!
+//! Which end of an item is taken first matters. Taking the least significant
+//! bit first spreads consecutive items evenly across the trie, while taking the
+//! most significant bit first makes the order of `items` numeric and makes a key
+//! prefix select a contiguous range of numbers. Past the end of the `32` bits,
+//! every bit is `false` and every nibble is `0`.
+//!
+//! ```
+//! # use bintrie::{keys, BinTrie};
+//! let mut trie = BinTrie::new_depth(32);
+//! for &n in &[3, 1, 2] {
+//!     trie.insert(n, keys::msb_key(n), keys::msb_bit);
+//! }
+//! assert_eq!(trie.get(keys::msb_key(2)), Some(2));
+//! assert_eq!(trie.items().collect::<Vec<u32>>(), vec![1, 2, 3]);
+//! ```
+
+/// Gets the `n`th bit of `item`, starting from the least significant bit.
+///
+/// This is the keying used by `FromIterator`, `Extend` and `insert_self_keyed`.
+#[inline(always)]
+pub fn lsb_bit(item: u32, n: u32) -> bool {
+    n < 32 && (item >> n) & 1 == 1
+}
+
+/// Gets the `n`th bit of `item`, starting from the most significant bit.
+#[inline(always)]
+pub fn msb_bit(item: u32, n: u32) -> bool {
+    n < 32 && (item >> (31 - n)) & 1 == 1
+}
+
+/// Makes a key from the bits of `key`, starting from the least significant bit.
+///
+/// ```
+/// # use bintrie::{keys, BinTrie};
+/// let trie: BinTrie = (0..10).collect();
+/// assert_eq!(trie.get(keys::lsb_key(7)), Some(7));
+/// ```
+#[inline(always)]
+pub fn lsb_key(key: u32) -> impl Fn(u32) -> bool + Copy {
+    move |n| lsb_bit(key, n)
+}
+
+/// Makes a key from the bits of `key`, starting from the most significant bit.
+#[inline(always)]
+pub fn msb_key(key: u32) -> impl Fn(u32) -> bool + Copy {
+    move |n| msb_bit(key, n)
+}
+
+/// Gets the `n`th nibble of `item`, starting from the least significant nibble.
+///
+/// This is meant for `BinTrieN<16>`, which takes a nibble at every level.
+#[inline(always)]
+pub fn lsb_nibble(item: u32, n: u32) -> usize {
+    if n < 8 {
+        (item >> (4 * n)) as usize & 0xF
+    } else {
+        0
+    }
+}
+
+/// Gets the `n`th nibble of `item`, starting from the most significant nibble.
+#[inline(always)]
+pub fn msb_nibble(item: u32, n: u32) -> usize {
+    if n < 8 {
+        (item >> (28 - 4 * n)) as usize & 0xF
+    } else {
+        0
+    }
+}
+
+/// Makes a key from the nibbles of `key`, starting from the least significant nibble.
+///
+/// ```
+/// # use bintrie::{keys, BinTrieN};
+/// let mut trie = BinTrieN::<16>::new_depth(8);
+/// for n in 0..100 {
+///     trie.insert(n, keys::lsb_nibble_key(n), keys::lsb_nibble);
+/// }
+/// assert_eq!(trie.get(keys::lsb_nibble_key(42)), Some(42));
+/// ```
+#[inline(always)]
+pub fn lsb_nibble_key(key: u32) -> impl Fn(u32) -> usize + Copy {
+    move |n| lsb_nibble(key, n)
+}
+
+/// Makes a key from the nibbles of `key`, starting from the most significant nibble.
+#[inline(always)]
+pub fn msb_nibble_key(key: u32) -> impl Fn(u32) -> usize + Copy {
+    move |n| msb_nibble(key, n)
+}
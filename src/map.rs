@@ -0,0 +1,213 @@
+use crate::{keys, BinTrie, Inserted};
+use alloc::{vec, vec::Vec};
+use core::{iter::FromIterator, mem};
+
+/// A trie which associates a value with each item.
+///
+/// The leaves of the underlying `BinTrie` store an index into a side vector of
+/// `(item, value)` pairs rather than the item itself. Compared to a plain
+/// `BinTrie`, this costs the size of an `(u32, V)` for every item and an extra
+/// indirection whenever the `lookup` function is called during insertion.
+/// Because the trie only stores indices, items may use the full `u32` range.
+#[derive(Clone, Debug)]
+pub struct BinTrieMap<V> {
+    trie: BinTrie,
+    entries: Vec<(u32, V)>,
+}
+
+impl<V> BinTrieMap<V> {
+    /// Makes a new map with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let map = BinTrieMap::<String>::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new map with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let map = BinTrieMap::<String>::new_depth(128);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        Self {
+            trie: BinTrie::new_depth(depth),
+            entries: vec![],
+        }
+    }
+
+    /// The number of items in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the map has no items.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts an item with an associated `value`.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// If the item was already present, its value is replaced. If a different
+    /// item occupied the final slot at the maximum depth, it is replaced. In both
+    /// cases the replaced item and value are returned.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let mut map = BinTrieMap::new();
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// assert_eq!(map.insert(5, "five", |l| lookup(5, l), lookup), None);
+    /// assert_eq!(map.insert(5, "FIVE", |l| lookup(5, l), lookup), Some((5, "five")));
+    /// assert_eq!(map.get_value(|l| lookup(5, l)), Some(&"FIVE"));
+    /// ```
+    pub fn insert<K, F>(
+        &mut self,
+        item: u32,
+        value: V,
+        mut key: K,
+        mut lookup: F,
+    ) -> Option<(u32, V)>
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u32, u32) -> bool,
+    {
+        // If the item is already present, only its value needs to be replaced.
+        if let Some(index) = self.trie.get(&mut key) {
+            let entry = &mut self.entries[index as usize];
+            if entry.0 == item {
+                return Some((item, mem::replace(&mut entry.1, value)));
+            }
+        }
+        let index = self.entries.len() as u32;
+        self.entries.push((item, value));
+        let entries = &self.entries;
+        let old = match self
+            .trie
+            .insert(index, &mut key, |i, n| lookup(entries[i as usize].0, n))
+        {
+            Inserted::DepthExhausted { replaced } => replaced,
+            _ => return None,
+        };
+        // A different item was replaced at the maximum depth, so move the new
+        // entry into its place and point the leaf at it.
+        let new = self.entries.pop().unwrap();
+        let old_entry = mem::replace(&mut self.entries[old as usize], new);
+        let entries = &self.entries;
+        self.trie
+            .insert(old, key, |i, n| lookup(entries[i as usize].0, n));
+        Some(old_entry)
+    }
+
+    /// Perform a lookup for the value of a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let mut map = BinTrieMap::new();
+    /// map.insert(5, "five", |_| false, |_, _| false);
+    /// assert_eq!(map.get_value(|_| false), Some(&"five"));
+    /// assert_eq!(map.get_value(|_| true), None);
+    /// ```
+    pub fn get_value<K>(&self, key: K) -> Option<&V>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        self.trie
+            .get(key)
+            .map(|index| &self.entries[index as usize].1)
+    }
+
+    /// Perform a lookup for the value of a particular item, allowing it to be changed.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let mut map = BinTrieMap::new();
+    /// map.insert(5, 1, |_| false, |_, _| false);
+    /// *map.get_value_mut(|_| false).unwrap() += 1;
+    /// assert_eq!(map.get_value(|_| false), Some(&2));
+    /// assert_eq!(map.get_value_mut(|_| true), None);
+    /// ```
+    pub fn get_value_mut<K>(&mut self, key: K) -> Option<&mut V>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        // The trie borrow ends once the index is read, so only `entries` is borrowed mutably.
+        let index = self.trie.get(key)?;
+        Some(&mut self.entries[index as usize].1)
+    }
+
+    /// Get an iterator over the items and values in the order they were inserted.
+    ///
+    /// ```
+    /// # use bintrie::BinTrieMap;
+    /// let mut map = BinTrieMap::new();
+    /// map.insert(3, "three", |_| false, |_, _| false);
+    /// map.insert(5, "five", |_| true, |_, _| true);
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), vec![(3, &"three"), (5, &"five")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &V)> {
+        self.entries.iter().map(|(item, value)| (*item, value))
+    }
+}
+
+impl<V> Default for BinTrieMap<V> {
+    fn default() -> Self {
+        Self {
+            trie: BinTrie::new(),
+            entries: vec![],
+        }
+    }
+}
+
+/// Makes a map using each item as its own key, like `FromIterator` for `BinTrie`.
+///
+/// If an item appears more than once, the last value wins, like with `HashMap`.
+///
+/// ```
+/// # use bintrie::{keys, BinTrieMap};
+/// let map: BinTrieMap<&str> = vec![(1, "one"), (2, "two"), (1, "uno")].into_iter().collect();
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map.get_value(keys::lsb_key(1)), Some(&"uno"));
+/// assert_eq!(map.get_value(keys::lsb_key(2)), Some(&"two"));
+/// ```
+impl<V> FromIterator<(u32, V)> for BinTrieMap<V> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (u32, V)>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// Inserts items with their values using each item as its own key, like `FromIterator`.
+///
+/// ```
+/// # use bintrie::{keys, BinTrieMap};
+/// let mut map: BinTrieMap<u32> = (0..4).map(|n| (n, n * 10)).collect();
+/// map.extend(vec![(3, 300), (u32::MAX, 0)]);
+/// assert_eq!(map.len(), 5);
+/// assert_eq!(map.get_value(keys::lsb_key(3)), Some(&300));
+/// assert_eq!(map.get_value(keys::lsb_key(u32::MAX)), Some(&0));
+/// ```
+impl<V> Extend<(u32, V)> for BinTrieMap<V> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (u32, V)>,
+    {
+        for (item, value) in iter {
+            self.insert(item, value, keys::lsb_key(item), keys::lsb_bit);
+        }
+    }
+}
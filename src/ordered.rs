@@ -0,0 +1,158 @@
+use crate::{BinTrie, Heuristic, IntoHeuristic, HIGH};
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+
+impl BinTrie {
+    /// Iterates over the trie in best-first order using the `heuristic` to prune.
+    ///
+    /// `P(level, side)` - A function that provides the cost of entering `side` at `level`.
+    ///
+    /// Unlike `explore`, which descends depth-first, this keeps every
+    /// unexplored branch in a priority queue keyed on the sum of the costs along
+    /// its path, and always continues with the cheapest one. Items are yielded
+    /// in ascending order of their accumulated cost, with ties yielded in the
+    /// order they were discovered. This allows stopping early once enough good
+    /// results were found, at the cost of keeping the whole frontier in memory.
+    ///
+    /// The `heuristic` still decides which sides may be entered at all. Note that
+    /// the cost of a leaf only covers the path to the leaf, since the remaining
+    /// bits of its key are not stored in the trie.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, FilterHeuristic};
+    /// let mut trie = BinTrie::new_depth(32);
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// for n in 0..16 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// // The cost is the Hamming distance to `5`.
+    /// let items = trie
+    ///     .explore_ordered(FilterHeuristic(|_| true), |l, side| u64::from(lookup(5, l) != side))
+    ///     .take(5)
+    ///     .collect::<Vec<u32>>();
+    /// assert_eq!(items[0], 5);
+    /// assert!(items[1..].iter().all(|n| (n ^ 5).count_ones() == 1));
+    /// ```
+    pub fn explore_ordered<'a, H, P>(
+        &'a self,
+        heuristic: H,
+        priority: P,
+    ) -> impl Iterator<Item = u32> + 'a
+    where
+        H: IntoHeuristic,
+        H::Heuristic: 'a,
+        P: Fn(u32, bool) -> u64 + 'a,
+    {
+        ExploreOrderedIter::new(self, heuristic.into_heuristic(), priority)
+    }
+}
+
+/// A slot waiting in the priority queue of `ExploreOrderedIter`.
+struct Candidate<H> {
+    /// The accumulated cost of the path to this slot.
+    cost: u64,
+    /// The order in which the candidate was discovered, used to break ties.
+    sequence: u64,
+    /// The contents of the slot, which is never empty.
+    slot: u32,
+    /// The level of the internal node this slot points to.
+    level: u32,
+    /// The heuristic after entering this slot, only kept for internal nodes.
+    heuristic: Option<H>,
+}
+
+impl<H> PartialEq for Candidate<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<H> Eq for Candidate<H> {}
+
+impl<H> PartialOrd for Candidate<H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<H> Ord for Candidate<H> {
+    /// Reversed so that the `BinaryHeap` pops the cheapest and oldest candidate first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .cmp(&self.cost)
+            .then(other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct ExploreOrderedIter<'a, H, P> {
+    trie: &'a BinTrie,
+    priority: P,
+    queue: BinaryHeap<Candidate<H>>,
+    sequence: u64,
+}
+
+impl<'a, H, P> ExploreOrderedIter<'a, H, P>
+where
+    H: Heuristic,
+    P: Fn(u32, bool) -> u64,
+{
+    fn new(trie: &'a BinTrie, heuristic: H, priority: P) -> Self {
+        let mut iter = Self {
+            trie,
+            priority,
+            queue: BinaryHeap::new(),
+            sequence: 0,
+        };
+        iter.expand(0, 0, 0, &heuristic);
+        iter
+    }
+
+    /// Adds the children of the internal node `index` to the queue.
+    fn expand(&mut self, index: u32, level: u32, cost: u64, heuristic: &H) {
+        let array = &self.trie.internals[index as usize].0;
+        for choice in heuristic.iter() {
+            let slot = array[if choice { 1 } else { 0 }];
+            if slot == 0 {
+                continue;
+            }
+            let heuristic = if slot & HIGH == 0 {
+                let mut heuristic = heuristic.clone();
+                heuristic.enter(choice);
+                Some(heuristic)
+            } else {
+                None
+            };
+            self.queue.push(Candidate {
+                cost: cost.saturating_add((self.priority)(level, choice)),
+                sequence: self.sequence,
+                slot,
+                level: level + 1,
+                heuristic,
+            });
+            self.sequence += 1;
+        }
+    }
+}
+
+impl<'a, H, P> Iterator for ExploreOrderedIter<'a, H, P>
+where
+    H: Heuristic,
+    P: Fn(u32, bool) -> u64,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let candidate = self.queue.pop()?;
+            match candidate.heuristic {
+                // Internal node
+                Some(heuristic) => {
+                    self.expand(candidate.slot, candidate.level, candidate.cost, &heuristic)
+                }
+                // Leaf node
+                None => return Some(self.trie.untag(candidate.slot)),
+            }
+        }
+    }
+}
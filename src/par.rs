@@ -0,0 +1,97 @@
+use crate::{BinTrie, HIGH};
+use alloc::vec::Vec;
+use rayon::iter::{
+    plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer},
+    ParallelIterator,
+};
+
+impl BinTrie {
+    /// Get a parallel iterator over the items added to the trie.
+    ///
+    /// The work is split along the subtrees of the trie, since separate subtrees
+    /// share nothing. Items are not yielded in any particular order.
+    ///
+    /// This requires the `rayon` feature.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// use rayon::prelude::*;
+    /// let trie: BinTrie = (0..1000).collect();
+    /// assert_eq!(trie.par_items().map(u64::from).sum::<u64>(), 999 * 1000 / 2);
+    /// ```
+    pub fn par_items(&self) -> impl ParallelIterator<Item = u32> + '_ {
+        ParItems {
+            producer: ItemsProducer {
+                trie: self,
+                slots: &self.internals[0].0,
+            },
+        }
+    }
+}
+
+struct ParItems<'a> {
+    producer: ItemsProducer<'a>,
+}
+
+impl<'a> ParallelIterator for ParItems<'a> {
+    type Item = u32;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(self.producer, consumer)
+    }
+}
+
+/// Produces the items below a run of sibling slots.
+struct ItemsProducer<'a> {
+    trie: &'a BinTrie,
+    slots: &'a [u32],
+}
+
+impl<'a> UnindexedProducer for ItemsProducer<'a> {
+    type Item = u32;
+
+    fn split(mut self) -> (Self, Option<Self>) {
+        // A single internal node is split by descending into its children.
+        while let [n] = *self.slots {
+            if n == 0 || n & HIGH != 0 {
+                return (self, None);
+            }
+            self.slots = &self.trie.internals[n as usize].0;
+        }
+        let (left, right) = self.slots.split_at(self.slots.len() / 2);
+        (
+            ItemsProducer {
+                trie: self.trie,
+                slots: left,
+            },
+            Some(ItemsProducer {
+                trie: self.trie,
+                slots: right,
+            }),
+        )
+    }
+
+    fn fold_with<F>(self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        let mut stack = self.slots.iter().rev().cloned().collect::<Vec<u32>>();
+        while let Some(n) = stack.pop() {
+            if folder.full() {
+                break;
+            }
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH != 0 => folder = folder.consume(self.trie.untag(n)),
+                // Internal node
+                n => stack.extend(self.trie.internals[n as usize].0.iter().rev()),
+            }
+        }
+        folder
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{BinTrie, HIGH};
+use alloc::{vec, vec::Vec};
+use rand::Rng;
+
+impl BinTrie {
+    /// Picks a random item by walking down from the root.
+    ///
+    /// At every internal node, one of the occupied sides is chosen with equal
+    /// probability until a leaf is reached, so this takes one step per level and
+    /// allocates nothing. Returns `None` if the trie is empty.
+    ///
+    /// The items are not equally likely. The chance of an item halves with every
+    /// level where the other side is also occupied, so an item alone in a sparse
+    /// subtree is picked far more often than one of the items in a dense subtree
+    /// at the same level. Use `sample_uniform` if every item must be equally likely.
+    ///
+    /// This requires the `rand` feature.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let trie: BinTrie = vec![0, 1, 3, 5, 7].into_iter().collect();
+    /// // `0` is alone on the `false` side of the root, while the other items share the `true` side.
+    /// let zeros = (0..1000).filter(|_| trie.sample(&mut rng) == Some(0)).count();
+    /// assert!(zeros > 400 && zeros < 600);
+    /// assert_eq!(BinTrie::new().sample(&mut rng), None);
+    /// ```
+    pub fn sample<R>(&self, rng: &mut R) -> Option<u32>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut index = 0;
+        loop {
+            let [a, b] = self.internals[index].0;
+            let n = match (a, b) {
+                (0, 0) => return None,
+                (n, 0) | (0, n) => n,
+                (a, b) => {
+                    if rng.gen() {
+                        b
+                    } else {
+                        a
+                    }
+                }
+            };
+            if n & HIGH != 0 {
+                return Some(self.untag(n));
+            }
+            index = n as usize;
+        }
+    }
+
+    /// Picks a random item, with every item equally likely.
+    ///
+    /// The number of items below every internal node is counted first, and then
+    /// the walk down from the root takes each side with a probability proportional
+    /// to the number of items on it. Counting visits every internal node and
+    /// allocates a count for each of them, so this takes `O(n)` time, unlike the
+    /// `O(depth)` of `sample`. When drawing many samples from a trie that does not
+    /// change, collecting `items` once and picking from it is cheaper.
+    ///
+    /// This requires the `rand` feature.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let trie: BinTrie = vec![0, 1, 3, 5, 7].into_iter().collect();
+    /// let zeros = (0..1000).filter(|_| trie.sample_uniform(&mut rng) == Some(0)).count();
+    /// assert!(zeros > 120 && zeros < 280);
+    /// assert_eq!(BinTrie::new().sample_uniform(&mut rng), None);
+    /// ```
+    pub fn sample_uniform<R>(&self, rng: &mut R) -> Option<u32>
+    where
+        R: Rng + ?Sized,
+    {
+        let counts = self.subtree_counts();
+        let count = |n: u32| match n {
+            0 => 0,
+            n if n & HIGH != 0 => 1,
+            n => counts[n as usize],
+        };
+        let mut index = 0;
+        loop {
+            let [a, b] = self.internals[index].0;
+            let total = count(a) + count(b);
+            if total == 0 {
+                return None;
+            }
+            let n = if rng.gen_range(0..total) < count(a) {
+                a
+            } else {
+                b
+            };
+            if n & HIGH != 0 {
+                return Some(self.untag(n));
+            }
+            index = n as usize;
+        }
+    }
+
+    /// Counts the items below every reachable internal node, indexed by the node.
+    fn subtree_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.internals.len()];
+        let order = self.internal_indices().collect::<Vec<usize>>();
+        // Children come after their parents in preorder, so go backwards.
+        for &index in order.iter().rev() {
+            counts[index] = self.internals[index]
+                .0
+                .iter()
+                .map(|&n| match n {
+                    0 => 0,
+                    n if n & HIGH != 0 => 1,
+                    n => counts[n as usize],
+                })
+                .sum();
+        }
+        counts
+    }
+}
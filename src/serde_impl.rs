@@ -0,0 +1,84 @@
+use crate::{BinTrie, Internal};
+use alloc::vec::Vec;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "BinTrie")]
+struct RawBinTrieRef<'a> {
+    internals: &'a [Internal],
+    depth: u32,
+    tag_bit: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "BinTrie")]
+struct RawBinTrie {
+    internals: Vec<Internal>,
+    depth: u32,
+    /// Data written before the tag bit could be chosen has no tag bit.
+    #[serde(default = "default_tag_bit")]
+    tag_bit: u32,
+}
+
+fn default_tag_bit() -> u32 {
+    31
+}
+
+/// Serializes the internal nodes, the depth and the tag bit.
+///
+/// ```
+/// # use bintrie::BinTrie;
+/// let mut trie = BinTrie::new_depth(32);
+/// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+/// for n in 0..100 {
+///     trie.insert(n, |l| lookup(n, l), lookup);
+/// }
+/// let bytes = bincode::serialize(&trie).unwrap();
+/// let trie: BinTrie = bincode::deserialize(&bytes).unwrap();
+/// assert_eq!(trie.len(), 100);
+/// assert_eq!(trie.get(|l| lookup(42, l)), Some(42));
+///
+/// let mut trie = BinTrie::new_depth_tag_bit(31, 4);
+/// trie.insert(1 << 31, |_| true, |_, _| true);
+/// let trie: BinTrie = bincode::deserialize(&bincode::serialize(&trie).unwrap()).unwrap();
+/// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![1 << 31]);
+/// ```
+impl Serialize for BinTrie {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawBinTrieRef {
+            internals: &self.internals,
+            depth: self.depth,
+            tag_bit: self.tag_bit,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes the trie, checking every invariant that `BinTrie::verify` checks.
+///
+/// In self-describing formats, data written before the tag bit was serialized
+/// has no `tag_bit` field and loads with the default tag bit of `31`, like
+/// version `1` of the format of `BinTrie::to_bytes`.
+///
+/// ```
+/// # use bintrie::BinTrie;
+/// // A root which points to internal node `5`, which does not exist.
+/// let bytes = bincode::serialize(&(vec![[5u32, 0]], 8u32, 31u32)).unwrap();
+/// assert!(bincode::deserialize::<BinTrie>(&bytes).is_err());
+/// ```
+impl<'de> Deserialize<'de> for BinTrie {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let RawBinTrie {
+            internals,
+            depth,
+            tag_bit,
+        } = RawBinTrie::deserialize(deserializer)?;
+        Self::from_internals(internals, depth, tag_bit).map_err(D::Error::custom)
+    }
+}
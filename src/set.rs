@@ -0,0 +1,448 @@
+use crate::{BinTrie, HIGH};
+use alloc::{vec, vec::Vec};
+
+impl BinTrie {
+    /// Checks if `item` itself is in the trie.
+    ///
+    /// Unlike `contains`, this makes sure the leaf the key leads to is `item`.
+    fn has_item<F>(&self, item: u32, lookup: &F) -> bool
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        self.get(|n| lookup(item, n)) == Some(item)
+    }
+
+    /// Makes an empty trie with the same depth, tag bit and collision policy.
+    fn empty_like(&self) -> BinTrie {
+        let mut trie = BinTrie::new_depth_tag_bit(self.depth, self.tag_bit);
+        trie.collision = self.collision;
+        trie
+    }
+
+    /// Makes a trie with the items that are in `self`, `other`, or both.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// Items are re-keyed into a new trie with `lookup`, so both tries must use
+    /// the same keys and the same depth.
+    ///
+    /// Panics if the depths of the tries differ.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// let b: BinTrie = vec![3, 4].into_iter().collect();
+    /// let c: BinTrie = vec![5].into_iter().collect();
+    /// assert_eq!(a.union(&b, lookup), vec![1, 2, 3, 4].into_iter().collect());
+    /// assert_eq!(a.union(&c, lookup), vec![1, 2, 3, 5].into_iter().collect());
+    /// assert_eq!(a.union(&a, lookup), a);
+    ///
+    /// // The result keeps the tag bit of `self`.
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut a = BinTrie::new_depth_tag_bit(32, 0);
+    /// a.insert(0x8000_0002, |l| lookup(0x8000_0002, l), lookup);
+    /// let mut b = BinTrie::new_depth_tag_bit(32, 0);
+    /// b.insert(2, |l| lookup(2, l), lookup);
+    /// let mut items = a.union(&b, lookup).items().collect::<Vec<u32>>();
+    /// items.sort();
+    /// assert_eq!(items, vec![2, 0x8000_0002]);
+    /// ```
+    pub fn union<F>(&self, other: &BinTrie, lookup: F) -> BinTrie
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        let mut trie = self.clone();
+        trie.merge(other, lookup);
+        trie
+    }
+
+    /// Inserts every item of `other` into `self`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// This is the in-place version of `union`, so it reuses the allocation of
+    /// `self`. Each item of `other` is re-keyed with `lookup`, which is why the
+    /// key is not taken separately.
+    ///
+    /// Panics if the depths of the tries differ.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut a: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// let b: BinTrie = vec![3, 4, 5].into_iter().collect();
+    /// a.merge(&b, lookup);
+    /// assert_eq!(a.len(), 5);
+    /// assert_eq!(a, vec![1, 2, 3, 4, 5].into_iter().collect());
+    /// ```
+    pub fn merge<F>(&mut self, other: &BinTrie, lookup: F)
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        assert_eq!(self.depth, other.depth);
+        for item in other.items() {
+            self.insert(item, |n| lookup(item, n), &lookup);
+        }
+    }
+
+    /// Merges any number of tries with the given `depth` into one.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of any of the tries.
+    ///
+    /// This is meant for building a large trie in parallel: each thread builds
+    /// its own trie from a shard of the items, and the shards are merged at the
+    /// end. The largest trie is kept as it is and the items of the others are
+    /// inserted into it with `merge`, so merging costs about as much as inserting
+    /// the items outside the largest shard.
+    ///
+    /// The result keeps the tag bit and collision policy of the largest trie.
+    /// With no tries, it is an empty trie with the default tag bit and policy.
+    ///
+    /// Panics if any of the tries does not have the given `depth`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+    /// let shards = (0..4)
+    ///     .map(|shard| std::thread::spawn(move || (shard * 100..(shard + 1) * 100).collect::<BinTrie>()))
+    ///     .collect::<Vec<_>>();
+    /// let tries = shards.into_iter().map(|shard| shard.join().unwrap());
+    /// let trie = BinTrie::merge_many(8192, tries, lookup);
+    /// assert_eq!(trie, (0..400).collect());
+    /// assert!(BinTrie::merge_many(8192, vec![], lookup).is_empty());
+    ///
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let shards = [0x8000_0002, 2].iter().map(|&n| {
+    ///     let mut trie = BinTrie::new_depth_tag_bit(32, 0);
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    ///     trie
+    /// });
+    /// let trie = BinTrie::merge_many(32, shards, lookup);
+    /// assert_eq!(trie.tag_bit(), 0);
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn merge_many<I, F>(depth: u32, tries: I, lookup: F) -> BinTrie
+    where
+        I: IntoIterator<Item = BinTrie>,
+        F: Fn(u32, u32) -> bool,
+    {
+        let mut tries = tries.into_iter().collect::<Vec<BinTrie>>();
+        for trie in &tries {
+            assert_eq!(trie.depth, depth);
+        }
+        let largest = match (0..tries.len()).max_by_key(|&i| tries[i].len) {
+            Some(largest) => largest,
+            None => return BinTrie::new_depth(depth),
+        };
+        let mut merged = tries.swap_remove(largest);
+        for trie in &tries {
+            merged.merge(trie, &lookup);
+        }
+        merged
+    }
+
+    /// Makes a trie with the items that are in both `self` and `other`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// Panics if the depths of the tries differ.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// let b: BinTrie = vec![3, 4].into_iter().collect();
+    /// let c: BinTrie = vec![5].into_iter().collect();
+    /// assert_eq!(a.intersection(&b, lookup), vec![3].into_iter().collect());
+    /// assert!(a.intersection(&c, lookup).is_empty());
+    /// assert_eq!(a.intersection(&a, lookup), a);
+    ///
+    /// // The result keeps the tag bit of `self`.
+    /// let mut a = BinTrie::new_depth_tag_bit(32, 0);
+    /// let mut b = BinTrie::new_depth_tag_bit(32, 0);
+    /// for &n in &[0x8000_0002, 2] {
+    ///     a.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// b.insert(0x8000_0002, |l| lookup(0x8000_0002, l), lookup);
+    /// let both = a.intersection(&b, lookup);
+    /// assert_eq!(both.tag_bit(), 0);
+    /// assert_eq!(both.items().collect::<Vec<u32>>(), vec![0x8000_0002]);
+    /// ```
+    pub fn intersection<F>(&self, other: &BinTrie, lookup: F) -> BinTrie
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        assert_eq!(self.depth, other.depth);
+        let mut trie = self.empty_like();
+        for item in self.items() {
+            if other.has_item(item, &lookup) {
+                trie.insert(item, |n| lookup(item, n), &lookup);
+            }
+        }
+        trie
+    }
+
+    /// Iterates over the items that are in both `self` and `other`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// Unlike `intersection`, no trie is built. Both tries are walked together
+    /// along the same paths, so subtrees that only one of them has are skipped
+    /// without being visited. Where one trie has a leaf and the other has an
+    /// internal node, the rest of the leaf's key is looked up in the other trie.
+    /// Only a stack as deep as the tries is allocated.
+    ///
+    /// Panics if the depths of the tries differ.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// # use std::collections::HashSet;
+    /// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+    /// let a_items = (0..1000u32).map(|n| n.wrapping_mul(2654435761) >> 21).collect::<HashSet<u32>>();
+    /// let b_items = (0..1000u32).map(|n| n.wrapping_mul(40503) % 2048).collect::<HashSet<u32>>();
+    /// let a: BinTrie = a_items.iter().cloned().collect();
+    /// let b: BinTrie = b_items.iter().cloned().collect();
+    /// let mut both = a.intersection_iter(&b, lookup).collect::<Vec<u32>>();
+    /// both.sort();
+    /// let mut expected = a_items.intersection(&b_items).cloned().collect::<Vec<u32>>();
+    /// expected.sort();
+    /// assert!(!expected.is_empty());
+    /// assert_eq!(both, expected);
+    /// ```
+    pub fn intersection_iter<'a, F>(
+        &'a self,
+        other: &'a BinTrie,
+        lookup: F,
+    ) -> impl Iterator<Item = u32> + 'a
+    where
+        F: Fn(u32, u32) -> bool + 'a,
+    {
+        assert_eq!(self.depth, other.depth);
+        let (a, b) = (self.internals[0].0, other.internals[0].0);
+        IntersectionIter {
+            a: self,
+            b: other,
+            lookup,
+            stack: vec![(a[1], b[1], 1), (a[0], b[0], 1)],
+        }
+    }
+
+    /// Makes a trie with the items that are in `self` but not in `other`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// Panics if the depths of the tries differ.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// let b: BinTrie = vec![3, 4].into_iter().collect();
+    /// let c: BinTrie = vec![5].into_iter().collect();
+    /// assert_eq!(a.difference(&b, lookup), vec![1, 2].into_iter().collect());
+    /// assert_eq!(a.difference(&c, lookup), a);
+    /// assert!(a.difference(&a, lookup).is_empty());
+    ///
+    /// // The result keeps the tag bit of `self`.
+    /// let mut a = BinTrie::new_depth_tag_bit(32, 0);
+    /// let mut b = BinTrie::new_depth_tag_bit(32, 0);
+    /// for &n in &[0x8000_0002, 2] {
+    ///     a.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// b.insert(2, |l| lookup(2, l), lookup);
+    /// let only_a = a.difference(&b, lookup);
+    /// assert_eq!(only_a.tag_bit(), 0);
+    /// assert_eq!(only_a.items().collect::<Vec<u32>>(), vec![0x8000_0002]);
+    /// ```
+    pub fn difference<F>(&self, other: &BinTrie, lookup: F) -> BinTrie
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        assert_eq!(self.depth, other.depth);
+        let mut trie = self.empty_like();
+        for item in self.items() {
+            if !other.has_item(item, &lookup) {
+                trie.insert(item, |n| lookup(item, n), &lookup);
+            }
+        }
+        trie
+    }
+
+    /// Checks if both tries contain the same items, regardless of their depths.
+    ///
+    /// This is like `==`, except that tries with different maximum depths are
+    /// equal as long as they contain the same items, such as a trie loaded with a
+    /// different configured depth. The raw items are compared, so no lookup is
+    /// needed. The items of both tries are collected and sorted, so this takes
+    /// `O(n log n)` time.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut a = BinTrie::new_depth(32);
+    /// a.extend(0..100);
+    /// let b: BinTrie = (0..100).collect();
+    /// assert_ne!(a, b);
+    /// assert!(a.items_eq(&b));
+    /// ```
+    pub fn items_eq(&self, other: &BinTrie) -> bool {
+        if self.len != other.len {
+            return false;
+        }
+        let mut a = self.items().collect::<Vec<u32>>();
+        let mut b = other.items().collect::<Vec<u32>>();
+        a.sort_unstable();
+        b.sort_unstable();
+        a == b
+    }
+
+    /// Checks if every item in `self` is also in `other`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// Every item of `self` is looked up in `other`. Each lookup walks down to
+    /// the leaf, which takes about `log2(other.len())` steps for well distributed
+    /// keys, so this takes `O(n log n)` time overall.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2].into_iter().collect();
+    /// let b: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// assert!(a.is_subset(&b, lookup));
+    /// assert!(!b.is_subset(&a, lookup));
+    /// ```
+    pub fn is_subset<F>(&self, other: &BinTrie, lookup: F) -> bool
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        self.len <= other.len && self.items().all(|item| other.has_item(item, &lookup))
+    }
+
+    /// Checks if every item in `other` is also in `self`.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// This is `other.is_subset(self, lookup)`, so it also takes `O(n log n)` time.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2].into_iter().collect();
+    /// let b: BinTrie = vec![1, 2, 3].into_iter().collect();
+    /// assert!(b.is_superset(&a, lookup));
+    /// assert!(!a.is_superset(&b, lookup));
+    /// ```
+    pub fn is_superset<F>(&self, other: &BinTrie, lookup: F) -> bool
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        other.is_subset(self, lookup)
+    }
+
+    /// Checks if `self` and `other` have no items in common.
+    ///
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from an item of either trie.
+    ///
+    /// This stops at the first shared item, but otherwise takes `O(n log n)` time
+    /// like `is_subset`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let a: BinTrie = vec![1, 2].into_iter().collect();
+    /// let b: BinTrie = vec![2, 3].into_iter().collect();
+    /// let c: BinTrie = vec![3, 4].into_iter().collect();
+    /// assert!(!a.is_disjoint(&b, lookup));
+    /// assert!(a.is_disjoint(&c, lookup));
+    /// ```
+    pub fn is_disjoint<F>(&self, other: &BinTrie, lookup: F) -> bool
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        // Probe the larger trie with the items of the smaller one.
+        let (small, large) = if self.len <= other.len {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        !small.items().any(|item| large.has_item(item, &lookup))
+    }
+}
+
+struct IntersectionIter<'a, F> {
+    a: &'a BinTrie,
+    b: &'a BinTrie,
+    lookup: F,
+    /// The slots of both tries at the same path, along with the length of the path.
+    stack: Vec<(u32, u32, u32)>,
+}
+
+impl<'a, F> IntersectionIter<'a, F>
+where
+    F: Fn(u32, u32) -> bool,
+{
+    /// Checks if `item` is below the internal node `index` of `trie` at `level`.
+    fn contains_below(&self, trie: &BinTrie, mut index: u32, mut level: u32, item: u32) -> bool {
+        loop {
+            let side = if (self.lookup)(item, level) { 1 } else { 0 };
+            match trie.internals[index as usize].0[side] {
+                0 => return false,
+                n if n & HIGH != 0 => return trie.untag(n) == item,
+                n => index = n,
+            }
+            level += 1;
+        }
+    }
+}
+
+impl<'a, F> Iterator for IntersectionIter<'a, F>
+where
+    F: Fn(u32, u32) -> bool,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (a, b, level) = self.stack.pop()?;
+            match (a, b) {
+                // Only one of the tries has anything here.
+                (0, _) | (_, 0) => {}
+                (a, b) if a & HIGH != 0 && b & HIGH != 0 => {
+                    // The tries may have different tag bits, so compare the items.
+                    if self.a.untag(a) == self.b.untag(b) {
+                        return Some(self.a.untag(a));
+                    }
+                }
+                (a, b) if a & HIGH != 0 => {
+                    let item = self.a.untag(a);
+                    if self.contains_below(self.b, b, level, item) {
+                        return Some(item);
+                    }
+                }
+                (a, b) if b & HIGH != 0 => {
+                    let item = self.b.untag(b);
+                    if self.contains_below(self.a, a, level, item) {
+                        return Some(item);
+                    }
+                }
+                // Both are internal nodes, so visit the `false` side first.
+                (a, b) => {
+                    let a = self.a.internals[a as usize].0;
+                    let b = self.b.internals[b as usize].0;
+                    self.stack.push((a[1], b[1], level + 1));
+                    self.stack.push((a[0], b[0], level + 1));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Functions to interpret the raw slots of the internal nodes of a `BinTrie`.
+//!
+//! Every internal node has a slot for each side, which is `0` if it is empty,
+//! holds an item tagged with the most significant bit if it is a leaf, and
+//! otherwise holds the index of another internal node. `BinTrie::raw_slot`
+//! gives the raw value of a slot, and these functions tell what it holds.
+//!
+//! ```
+//! # use bintrie::{slot, BinTrie};
+//! let trie: BinTrie = (0..4).collect();
+//! let raw = trie.raw_slot(|_| true, 1).unwrap();
+//! assert!(slot::is_internal(raw));
+//! assert_eq!(trie.node_occupancy(slot::untag(raw) as usize), Some(0b11));
+//! let raw = trie.raw_slot(|_| true, 2).unwrap();
+//! assert!(slot::is_leaf(raw));
+//! assert_eq!(slot::untag(raw), 3);
+//! ```
+
+use crate::{BinTrie, HIGH};
+
+/// Checks if the slot holds nothing.
+#[inline(always)]
+pub fn is_empty(slot: u32) -> bool {
+    slot == 0
+}
+
+/// Checks if the slot holds a leaf.
+#[inline(always)]
+pub fn is_leaf(slot: u32) -> bool {
+    slot & HIGH != 0
+}
+
+/// Checks if the slot holds the index of an internal node.
+#[inline(always)]
+pub fn is_internal(slot: u32) -> bool {
+    slot != 0 && slot & HIGH == 0
+}
+
+/// Gets the item of a leaf or the index of an internal node.
+///
+/// This clears the most significant bit, so it leaves an index unchanged. The
+/// item is only correct for a trie with the default tag bit of `31`, so use
+/// `BinTrie::untag` for a trie made with `BinTrie::new_depth_tag_bit`.
+#[inline(always)]
+pub fn untag(slot: u32) -> u32 {
+    slot & !HIGH
+}
+
+impl BinTrie {
+    /// Gets the raw value of the slot reached by the first `prefix_len` bits of the key.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// The walk stops early at a leaf, which is returned as it is, like
+    /// `BinTrie::subtree`. Returns `None` if the slot is empty, if `prefix_len` is
+    /// `0`, since the root is not held in any slot, or if `prefix_len` is larger
+    /// than the depth. Use the functions of the `slot` module to interpret the
+    /// value. The index of an internal node can be passed to `node_occupancy`,
+    /// and is only meaningful until the trie is modified.
+    ///
+    /// ```
+    /// # use bintrie::{slot, BinTrie};
+    /// let mut trie = BinTrie::new();
+    /// trie.insert(5, |_| true, |_, _| true);
+    /// assert_eq!(trie.raw_slot(|_| true, 0), None);
+    /// assert_eq!(trie.raw_slot(|_| false, 1), None);
+    /// assert_eq!(trie.raw_slot(|_| true, 1).map(slot::untag), Some(5));
+    /// // The leaf is reached before the end of the prefix.
+    /// assert_eq!(trie.raw_slot(|_| true, 8).map(slot::is_leaf), Some(true));
+    /// ```
+    pub fn raw_slot<K>(&self, mut key: K, prefix_len: u32) -> Option<u32>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        if prefix_len == 0 || prefix_len > self.depth {
+            return None;
+        }
+        let mut index = 0;
+        for level in 0..prefix_len {
+            match self.internals[index].0[if key(level) { 1 } else { 0 }] {
+                0 => return None,
+                n if n & HIGH != 0 || level + 1 == prefix_len => return Some(n),
+                n => index = n as usize,
+            }
+        }
+        None
+    }
+}
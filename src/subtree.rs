@@ -0,0 +1,116 @@
+use crate::{BinTrie, ExploreIter, FilterHeuristic, Heuristic, IntoHeuristic, HIGH};
+use alloc::{vec, vec::Vec};
+use core::iter::FusedIterator;
+
+/// A view of the part of a `BinTrie` below a key prefix.
+///
+/// Made by `BinTrie::subtree`, which walks the prefix once so that the subtree
+/// can be iterated or explored any number of times without walking it again.
+#[derive(Copy, Clone, Debug)]
+pub struct Subtree<'a> {
+    trie: &'a BinTrie,
+    /// The slot the prefix led to, which is either a leaf or an internal node.
+    slot: u32,
+    /// The number of levels walked to reach the slot.
+    level: u32,
+}
+
+impl BinTrie {
+    /// Gets a view of the items whose keys start with the first `prefix_len` bits of `prefix`.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the prefix.
+    ///
+    /// Returns `None` if the prefix leads to an empty slot. If it leads to a leaf
+    /// before `prefix_len` bits, the subtree only holds that leaf, just like
+    /// `explore_prefix` yields leaves found above the end of the prefix.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let trie: BinTrie = (0..16).collect();
+    /// // Keys start from the least significant bit.
+    /// let odd = trie.subtree(|_| true, 1).unwrap();
+    /// assert_eq!(odd.items().count(), 8);
+    /// assert!(odd.items().all(|n| n % 2 == 1));
+    /// assert_eq!(trie.subtree(|_| true, 4).unwrap().items().collect::<Vec<u32>>(), vec![15]);
+    /// // The leaf is found after `4` levels, so the rest of the prefix is not needed.
+    /// assert_eq!(trie.subtree(|_| true, 100).unwrap().level(), 4);
+    /// let trie: BinTrie = vec![0].into_iter().collect();
+    /// assert!(trie.subtree(|_| true, 1).is_none());
+    /// ```
+    pub fn subtree<K>(&self, mut prefix: K, prefix_len: u32) -> Option<Subtree<'_>>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        // The root is the internal node at index `0`.
+        let mut slot = 0;
+        for level in 0..prefix_len {
+            if slot & HIGH != 0 {
+                return Some(Subtree {
+                    trie: self,
+                    slot,
+                    level,
+                });
+            }
+            match self.internals[slot as usize].0[if prefix(level) { 1 } else { 0 }] {
+                // Empty node encountered.
+                0 => return None,
+                // Leaf or internal node encountered.
+                n => slot = n,
+            }
+        }
+        Some(Subtree {
+            trie: self,
+            slot,
+            level: prefix_len,
+        })
+    }
+}
+
+impl<'a> Subtree<'a> {
+    /// The number of levels walked from the root of the trie to reach the subtree.
+    ///
+    /// This is less than the prefix length if the prefix led to a leaf early.
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Get an iterator over the items in the subtree.
+    ///
+    /// The items are yielded in the same order as `BinTrie::items`.
+    pub fn items(&self) -> impl FusedIterator<Item = u32> + 'a {
+        self.explore(FilterHeuristic(|_| true))
+    }
+
+    /// Iterates over the subtree using the `heuristic` to prune it.
+    ///
+    /// The heuristic starts at the root of the subtree, so it is only entered for
+    /// the sides taken below the prefix.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, FilterHeuristic};
+    /// let trie: BinTrie = (0..16).collect();
+    /// let odd = trie.subtree(|_| true, 1).unwrap();
+    /// let items = odd.explore(FilterHeuristic(|side: bool| !side)).collect::<Vec<u32>>();
+    /// assert_eq!(items, vec![1]);
+    /// ```
+    pub fn explore<H>(&self, heuristic: H) -> impl FusedIterator<Item = u32> + 'a
+    where
+        H: IntoHeuristic,
+        H::Heuristic: 'a,
+    {
+        let (leaf, indices) = if self.slot & HIGH != 0 {
+            (Some(self.trie.untag(self.slot)), Vec::new())
+        } else {
+            let heuristic = heuristic.into_heuristic();
+            let iter = heuristic.iter();
+            (
+                None,
+                vec![(&self.trie.internals[self.slot as usize].0, heuristic, iter)],
+            )
+        };
+        leaf.into_iter().chain(ExploreIter {
+            trie: self.trie,
+            indices,
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{BinTrie, HIGH};
+
+/// Swaps bit `tag_bit` of `n` with its most significant bit.
+#[inline(always)]
+fn swap_tag_bit(n: u32, tag_bit: u32) -> u32 {
+    // Nothing is swapped by default, and checking for it keeps the default as
+    // fast as it was before the tag bit could be chosen.
+    if tag_bit == 31 {
+        return n;
+    }
+    let diff = (n ^ (n >> (31 - tag_bit))) & (1 << tag_bit);
+    n ^ diff ^ (diff << (31 - tag_bit))
+}
+
+/// Gets the item stored in the leaf `slot` of a trie with the given tag bit.
+#[inline(always)]
+pub(crate) fn untag(slot: u32, tag_bit: u32) -> u32 {
+    swap_tag_bit(slot & !HIGH, tag_bit)
+}
+
+impl BinTrie {
+    /// Makes a new trie with a given maximum `depth` whose items keep `tag_bit` clear.
+    ///
+    /// By default, items must keep their most significant bit clear, since leaves
+    /// are told apart from internal nodes by setting it. With a different tag bit,
+    /// the tag bit of an item is swapped with its most significant bit when it is
+    /// stored in a leaf and swapped back when it is read, so items may use the
+    /// most significant bit but must keep `tag_bit` clear instead. Inserting an
+    /// item with the tag bit set fails like inserting one with the most
+    /// significant bit set normally does. The items passed to `lookup` and
+    /// returned by every method are the items as they were inserted.
+    ///
+    /// The layout of the internal nodes does not change, so there is no limit on
+    /// the number of internal nodes beyond the usual one.
+    ///
+    /// Panics if `tag_bit` is not less than `32` or if `depth` is `0`.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie, BinTrieError};
+    /// // Keep the lowest bit free instead of the highest.
+    /// let mut trie = BinTrie::new_depth_tag_bit(32, 0);
+    /// let lookup = |n: u32, l: u32| (n >> (31 - l)) & 1 == 1;
+    /// trie.insert(0x8000_0002, |l| lookup(0x8000_0002, l), lookup);
+    /// trie.insert(0x4000_0000, |l| lookup(0x4000_0000, l), lookup);
+    /// assert_eq!(trie.get(|l| lookup(0x8000_0002, l)), Some(0x8000_0002));
+    /// assert_eq!(trie.items().collect::<Vec<u32>>(), vec![0x4000_0000, 0x8000_0002]);
+    /// assert_eq!(trie.try_insert(3, |l| lookup(3, l), lookup), Err(BinTrieError::InvalidItem));
+    /// ```
+    pub fn new_depth_tag_bit(depth: u32, tag_bit: u32) -> Self {
+        assert!(tag_bit < 32);
+        let mut trie = Self::new_depth(depth);
+        trie.tag_bit = tag_bit;
+        trie
+    }
+
+    /// The bit that items must keep clear, which is `31` unless chosen with `new_depth_tag_bit`.
+    pub fn tag_bit(&self) -> u32 {
+        self.tag_bit
+    }
+
+    /// Gets the item stored in a leaf slot, such as one returned by `raw_slot`.
+    ///
+    /// This is `slot::untag` for the tag bit of this trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let mut trie = BinTrie::new_depth_tag_bit(32, 0);
+    /// trie.insert(u32::MAX - 1, |_| true, |_, _| true);
+    /// assert_eq!(trie.untag(trie.raw_slot(|_| true, 1).unwrap()), u32::MAX - 1);
+    /// ```
+    #[inline(always)]
+    pub fn untag(&self, slot: u32) -> u32 {
+        untag(slot, self.tag_bit)
+    }
+
+    /// Makes the leaf slot that stores `item`.
+    #[inline(always)]
+    pub(crate) fn tag(&self, item: u32) -> u32 {
+        swap_tag_bit(item, self.tag_bit) | HIGH
+    }
+}
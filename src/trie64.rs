@@ -0,0 +1,316 @@
+use crate::{Heuristic, Inserted, IntoHeuristic};
+use alloc::{vec, vec::Vec};
+use core::slice;
+
+const HIGH64: u64 = 0x8000_0000_0000_0000;
+
+/// Contains a list of 2 children node IDs.
+///
+/// This is the same as the `Internal` node of `BinTrie`, but with `u64` slots.
+#[derive(Copy, Clone, Debug, Default)]
+struct Internal64([u64; 2]);
+
+/// A `BinTrie` which stores `u64` items.
+///
+/// The most significant bit is used to tag leaves, so items may use the lower
+/// `63` bits. Every internal node takes twice as much memory as in `BinTrie`.
+/// The methods mirror those of `BinTrie`, so migrating only requires changing
+/// the item type.
+#[derive(Clone, Debug)]
+pub struct BinTrie64 {
+    /// The root node is always at index `0`.
+    internals: Vec<Internal64>,
+    /// The maximum depth to stop at.
+    depth: u32,
+    /// The number of items in the trie.
+    len: usize,
+}
+
+impl BinTrie64 {
+    /// Makes a new trie with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie64;
+    /// let trie = BinTrie64::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new trie with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie64;
+    /// let trie = BinTrie64::new_depth(128);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        assert!(depth > 0);
+        Self {
+            internals: vec![Internal64::default()],
+            depth,
+            len: 0,
+        }
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a number that does not have the most significant bit set.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// Returns whether the item was new, already present, or replaced a
+    /// different item because the maximum depth was exhausted.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie64;
+    /// let mut trie = BinTrie64::new();
+    /// trie.insert(1 << 40, |_| false, |_, _| false);
+    /// assert_eq!(trie.items().collect::<Vec<u64>>(), vec![1 << 40]);
+    /// ```
+    #[inline(always)]
+    pub fn insert<K, F>(&mut self, item: u64, mut key: K, mut lookup: F) -> Inserted<u64>
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u64, u32) -> bool,
+    {
+        // Always check that the high bit is not set in the item.
+        assert!(item & HIGH64 == 0);
+        // This unsafe block is only used to allow indexing [u64; 2] by a `1` or `0`.
+        unsafe {
+            let mut index = 0;
+            for i in 0..self.depth - 1 {
+                debug_assert!(index < self.internals.len());
+                let position = if key(i) { 1 } else { 0 };
+                match *self
+                    .internals
+                    .get_unchecked(index)
+                    .0
+                    .get_unchecked(position)
+                {
+                    // Empty node encountered.
+                    0 => {
+                        *self
+                            .internals
+                            .get_unchecked_mut(index)
+                            .0
+                            .get_unchecked_mut(position) = item | HIGH64;
+                        self.len += 1;
+                        return Inserted::New;
+                    }
+                    // The item itself is already present.
+                    m if m == item | HIGH64 => return Inserted::AlreadyPresent,
+                    // Leaf node encountered.
+                    m if m & HIGH64 != 0 => {
+                        // Move the existing leaf into a new internal node.
+                        let mut new_internal = Internal64::default();
+                        *new_internal
+                            .0
+                            .get_unchecked_mut(if lookup(m & !HIGH64, i + 1) { 1 } else { 0 }) = m;
+                        let new_index = self.internals.len() as u64;
+                        self.internals.push(new_internal);
+                        *self
+                            .internals
+                            .get_unchecked_mut(index)
+                            .0
+                            .get_unchecked_mut(position) = new_index;
+                        index = new_index as usize;
+                    }
+                    // Internal node encountered.
+                    m => {
+                        index = m as usize;
+                    }
+                }
+            }
+
+            // For the last bit we replace whatever occupies the space.
+            let position = if key(self.depth - 1) { 1 } else { 0 };
+            debug_assert!(index < self.internals.len());
+            let spot = self
+                .internals
+                .get_unchecked_mut(index)
+                .0
+                .get_unchecked_mut(position);
+            let old = *spot;
+            *spot = item | HIGH64;
+            match old {
+                0 => {
+                    self.len += 1;
+                    Inserted::New
+                }
+                m if m == item | HIGH64 => Inserted::AlreadyPresent,
+                m => Inserted::DepthExhausted {
+                    replaced: m & !HIGH64,
+                },
+            }
+        }
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie64;
+    /// let mut trie = BinTrie64::new();
+    /// trie.insert(1 << 40, |_| false, |_, _| false);
+    /// assert_eq!(trie.get(|_| false), Some(1 << 40));
+    /// assert_eq!(trie.get(|_| true), None);
+    /// ```
+    #[inline(always)]
+    pub fn get<K>(&self, mut key: K) -> Option<u64>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        // This unsafe block is only used to allow indexing [u64; 2] by a `1` or `0`.
+        unsafe {
+            let mut index = 0;
+            for i in 0..self.depth {
+                debug_assert!(index < self.internals.len());
+                match *self
+                    .internals
+                    .get_unchecked(index)
+                    .0
+                    .get_unchecked(if key(i) { 1 } else { 0 })
+                {
+                    // Empty node encountered.
+                    0 => return None,
+                    // Leaf node encountered.
+                    m if m & HIGH64 != 0 => return Some(m & !HIGH64),
+                    // Internal node encountered.
+                    m => index = m as usize,
+                }
+            }
+            None
+        }
+    }
+
+    /// Get an iterator over the items added to the trie.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie64;
+    /// let mut trie = BinTrie64::new();
+    /// trie.insert(3, |_| false, |_, _| false);
+    /// assert_eq!(trie.items().collect::<Vec<u64>>(), vec![3]);
+    /// ```
+    pub fn items<'a>(&'a self) -> impl Iterator<Item = u64> + 'a {
+        Iter64 {
+            trie: self,
+            indices: vec![self.internals[0].0.iter()],
+        }
+    }
+
+    /// Iterates over the trie while using the `heuristic` to guide iteration.
+    ///
+    /// See `BinTrie::explore` for details.
+    ///
+    /// ```
+    /// # use bintrie::{BinTrie64, FilterHeuristic};
+    /// let mut trie = BinTrie64::new();
+    /// trie.insert(3, |_| false, |_, _| false);
+    /// trie.insert(7, |_| true, |_, _| true);
+    /// assert_eq!(trie.explore(FilterHeuristic(|n| n)).collect::<Vec<u64>>(), vec![7]);
+    /// ```
+    pub fn explore<'a, H>(&'a self, heuristic: H) -> impl Iterator<Item = u64> + 'a
+    where
+        H: IntoHeuristic,
+        H::Heuristic: 'a,
+    {
+        let heuristic = heuristic.into_heuristic();
+        let iter = heuristic.iter();
+        ExploreIter64 {
+            trie: self,
+            indices: vec![(&self.internals[0].0, heuristic, iter)],
+        }
+    }
+}
+
+impl Default for BinTrie64 {
+    fn default() -> Self {
+        Self {
+            internals: vec![Internal64::default()],
+            depth: 8192,
+            len: 0,
+        }
+    }
+}
+
+struct Iter64<'a> {
+    trie: &'a BinTrie64,
+    indices: Vec<slice::Iter<'a, u64>>,
+}
+
+impl<'a> Iterator for Iter64<'a> {
+    type Item = u64;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut current = self.indices.pop()?;
+            let n = if let Some(n) = current.next() {
+                self.indices.push(current);
+                n
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH64 != 0 => return Some(n & !HIGH64),
+                // Internal node
+                &n => self.indices.push(self.trie.internals[n as usize].0.iter()),
+            }
+        }
+    }
+}
+
+struct ExploreIter64<'a, H>
+where
+    H: Heuristic,
+{
+    trie: &'a BinTrie64,
+    indices: Vec<(&'a [u64; 2], H, H::Iter)>,
+}
+
+impl<'a, H> Iterator for ExploreIter64<'a, H>
+where
+    H: Heuristic,
+{
+    type Item = u64;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (array, heuristic, mut iter) = self.indices.pop()?;
+            let mut next_heuristic = heuristic.clone();
+            let (choice, n) = if let Some(choice) = iter.next() {
+                let n = unsafe { array.get_unchecked(if choice { 1 } else { 0 }) };
+                self.indices.push((array, heuristic, iter));
+                (choice, n)
+            } else {
+                continue;
+            };
+            match n {
+                // Empty node
+                0 => {}
+                // Leaf node
+                n if n & HIGH64 != 0 => return Some(n & !HIGH64),
+                // Internal node
+                &n => {
+                    next_heuristic.enter(choice);
+                    let iter = next_heuristic.iter();
+                    self.indices
+                        .push((&self.trie.internals[n as usize].0, next_heuristic, iter))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{BinTrie, HIGH};
+use alloc::vec;
+use core::fmt;
+
+/// A broken structural invariant found by `BinTrie::verify`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// There are no internal nodes, so the root is missing.
+    MissingRoot,
+    /// A slot of an internal node points to an internal node that does not exist.
+    InvalidIndex {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// A slot points to an internal node which was already reached through
+    /// another slot, which includes any cycle back up the trie.
+    Revisited {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// A slot points to an internal node which would sit past the maximum depth.
+    TooDeep {
+        /// The internal node containing the bad slot.
+        node: usize,
+        /// The slot of the internal node.
+        slot: usize,
+    },
+    /// The number of reachable leaves does not match `len`.
+    LenMismatch {
+        /// The number of items the trie claims to have.
+        len: usize,
+        /// The number of leaves reachable from the root.
+        leaves: usize,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::MissingRoot => write!(f, "missing root node"),
+            VerifyError::InvalidIndex { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points to a missing node",
+                slot, node
+            ),
+            VerifyError::Revisited { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points to an already reachable node",
+                slot, node
+            ),
+            VerifyError::TooDeep { node, slot } => write!(
+                f,
+                "slot {} of internal node {} points past the maximum depth",
+                slot, node
+            ),
+            VerifyError::LenMismatch { len, leaves } => write!(
+                f,
+                "trie has a length of {} but {} reachable leaves",
+                len, leaves
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+impl BinTrie {
+    /// Checks the structural invariants of the trie.
+    ///
+    /// This checks that the root exists, that every slot points to an internal
+    /// node which exists, that every internal node is reachable through exactly
+    /// one slot (so there are no cycles), that no internal node sits past the
+    /// maximum depth, and that the number of leaves matches `len`. A slot can
+    /// never point back to the root because `0` marks an empty slot.
+    ///
+    /// The other methods assume these invariants hold. `from_bytes` and
+    /// deserialization already check them, so this is a safety net for tries
+    /// built with `from_raw` and for debugging.
+    ///
+    /// ```
+    /// # use bintrie::BinTrie;
+    /// let trie: BinTrie = (0..100).collect();
+    /// assert_eq!(trie.verify(), Ok(()));
+    /// ```
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        if self.internals.is_empty() {
+            return Err(VerifyError::MissingRoot);
+        }
+        let mut visited = vec![false; self.internals.len()];
+        visited[0] = true;
+        let mut leaves = 0;
+        let mut stack = vec![(0, 0)];
+        while let Some((node, level)) = stack.pop() {
+            for (slot, &n) in self.internals[node].0.iter().enumerate() {
+                match n {
+                    // Empty node
+                    0 => {}
+                    // Leaf node
+                    n if n & HIGH != 0 => leaves += 1,
+                    // Internal node
+                    n => {
+                        let n = n as usize;
+                        if n >= self.internals.len() {
+                            return Err(VerifyError::InvalidIndex { node, slot });
+                        }
+                        if visited[n] {
+                            return Err(VerifyError::Revisited { node, slot });
+                        }
+                        if level + 1 >= self.depth {
+                            return Err(VerifyError::TooDeep { node, slot });
+                        }
+                        visited[n] = true;
+                        stack.push((n, level + 1));
+                    }
+                }
+            }
+        }
+        if leaves != self.len {
+            return Err(VerifyError::LenMismatch {
+                len: self.len,
+                leaves,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{BinTrie, Inserted, HIGH};
+use alloc::{vec, vec::Vec};
+use core::iter::FusedIterator;
+
+/// A trie which keeps the number of items below every internal node.
+///
+/// The counts are kept in a `Vec<u32>` alongside the internal nodes of the
+/// underlying `BinTrie` and are updated along the key's path on every insertion
+/// and removal. This makes counting the items under a prefix take one step per
+/// level of the prefix instead of visiting the whole subtree, and lets
+/// `sample_uniform` pick a random item in `O(depth)` time. In exchange, every
+/// internal node takes `12` bytes instead of `8`, and insertion and removal walk
+/// the key's path again to update the counts.
+///
+/// ```
+/// # use bintrie::WeightedBinTrie;
+/// let lookup = |n: u32, l: u32| l < 32 && (n >> l) & 1 == 1;
+/// let mut trie = WeightedBinTrie::new();
+/// for n in 0..100 {
+///     trie.insert(n, |l| lookup(n, l), lookup);
+/// }
+/// for n in (0..100).step_by(3) {
+///     trie.remove(n, |l| lookup(n, l));
+/// }
+/// trie.insert(1000, |l| lookup(1000, l), lookup);
+/// assert_eq!(trie.len(), 67);
+/// assert_eq!(trie.subtree_len(|_| false, 0), 67);
+/// // The counts agree with the items found under every prefix of up to `4` levels.
+/// for prefix_len in 0..=4 {
+///     for prefix in 0..1 << prefix_len {
+///         let expected = trie
+///             .as_trie()
+///             .subtree(|l| lookup(prefix, l), prefix_len)
+///             .map_or(0, |subtree| subtree.items().count());
+///         assert_eq!(trie.subtree_len(|l| lookup(prefix, l), prefix_len), expected);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WeightedBinTrie {
+    trie: BinTrie,
+    /// The number of items below every internal node, indexed like the internal nodes.
+    counts: Vec<u32>,
+}
+
+impl WeightedBinTrie {
+    /// Makes a new trie with a maximum `depth` of `8192`.
+    ///
+    /// ```
+    /// # use bintrie::WeightedBinTrie;
+    /// let trie = WeightedBinTrie::new();
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes a new trie with a given maximum `depth`.
+    ///
+    /// ```
+    /// # use bintrie::WeightedBinTrie;
+    /// let trie = WeightedBinTrie::new_depth(32);
+    /// ```
+    pub fn new_depth(depth: u32) -> Self {
+        Self {
+            trie: BinTrie::new_depth(depth),
+            counts: vec![0],
+        }
+    }
+
+    /// The underlying trie, which can be used for any read-only operation.
+    pub fn as_trie(&self) -> &BinTrie {
+        &self.trie
+    }
+
+    /// The number of items in the trie.
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    /// Checks if the trie has no items.
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// Inserts a number that does not have the most significant bit set.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    /// `F(item, n)` - A function that must be able to look up the nth bit
+    ///    from a previously inserted item.
+    ///
+    /// This behaves like `BinTrie::insert`. The key is walked once more before
+    /// and after inserting to update the counts, so it must give the same bits
+    /// every time.
+    ///
+    /// ```
+    /// # use bintrie::{Inserted, WeightedBinTrie};
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = WeightedBinTrie::new_depth(2);
+    /// assert_eq!(trie.insert(1, |l| lookup(1, l), lookup), Inserted::New);
+    /// assert_eq!(trie.insert(3, |l| lookup(3, l), lookup), Inserted::New);
+    /// assert_eq!(trie.insert(5, |l| lookup(5, l), lookup), Inserted::DepthExhausted { replaced: 1 });
+    /// assert_eq!(trie.subtree_len(|_| true, 1), 2);
+    /// ```
+    pub fn insert<K, F>(&mut self, item: u32, mut key: K, lookup: F) -> Inserted
+    where
+        K: FnMut(u32) -> bool,
+        F: FnMut(u32, u32) -> bool,
+    {
+        // Count the internal nodes that already held the path before inserting.
+        let mut existing = 1;
+        let mut slot = self.child(0, key(0));
+        while slot != 0 && slot & HIGH == 0 {
+            slot = self.child(slot, key(existing));
+            existing += 1;
+        }
+        let inserted = self.trie.insert(item, &mut key, lookup);
+        let added = match inserted {
+            Inserted::New => 1,
+            // A leaf may still have been split all the way down before being replaced.
+            Inserted::DepthExhausted { .. } | Inserted::Discarded { .. } => 0,
+            Inserted::AlreadyPresent => return inserted,
+        };
+        self.counts.resize(self.trie.internals.len(), 0);
+        // The existing nodes gained the item, while the new ones only hold the
+        // leaf that was split and the item, if it was added.
+        let mut level = 0;
+        let mut slot = 0;
+        loop {
+            if level < existing {
+                self.counts[slot as usize] += added;
+            } else {
+                self.counts[slot as usize] = 1 + added;
+            }
+            slot = self.child(slot, key(level));
+            level += 1;
+            if slot & HIGH != 0 {
+                break;
+            }
+        }
+        inserted
+    }
+
+    /// Removes a previously inserted item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// This behaves like `BinTrie::remove`. The key is walked once more after
+    /// removing to update the counts.
+    ///
+    /// ```
+    /// # use bintrie::WeightedBinTrie;
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut trie = WeightedBinTrie::new_depth(32);
+    /// for n in 0..8 {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// assert!(trie.remove(6, |l| lookup(6, l)));
+    /// assert!(!trie.remove(6, |l| lookup(6, l)));
+    /// assert_eq!(trie.subtree_len(|_| false, 1), 3);
+    /// ```
+    pub fn remove<K>(&mut self, item: u32, mut key: K) -> bool
+    where
+        K: FnMut(u32) -> bool,
+    {
+        if !self.trie.remove(item, &mut key) {
+            return false;
+        }
+        // Removal only collapses nodes at the end of the path, so every internal
+        // node still on the path held the item.
+        let mut level = 0;
+        let mut slot = 0;
+        loop {
+            self.counts[slot as usize] -= 1;
+            slot = self.child(slot, key(level));
+            level += 1;
+            if slot == 0 || slot & HIGH != 0 {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Perform a lookup for a particular item.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the key.
+    ///
+    /// This is the same as `BinTrie::get`.
+    pub fn get<K>(&self, key: K) -> Option<u32>
+    where
+        K: FnMut(u32) -> bool,
+    {
+        self.trie.get(key)
+    }
+
+    /// Get an iterator over the items added to the trie.
+    pub fn items(&self) -> impl ExactSizeIterator<Item = u32> + FusedIterator + '_ {
+        self.trie.items()
+    }
+
+    /// The number of items whose keys start with the first `prefix_len` bits of `prefix`.
+    ///
+    /// `K(n)` - A function that provides the `n`th bit for the prefix.
+    ///
+    /// This counts the same items as `BinTrie::subtree`, including a leaf found
+    /// above the end of the prefix, but only walks the prefix.
+    pub fn subtree_len<K>(&self, mut prefix: K, prefix_len: u32) -> usize
+    where
+        K: FnMut(u32) -> bool,
+    {
+        let mut slot = 0;
+        for level in 0..prefix_len {
+            if slot & HIGH != 0 {
+                break;
+            }
+            slot = self.child(slot, prefix(level));
+            if slot == 0 {
+                return 0;
+            }
+        }
+        if slot == 0 {
+            // Only an empty prefix stays at the root.
+            return self.len();
+        }
+        self.count(slot) as usize
+    }
+
+    /// The number of items below the internal node at index `node`.
+    ///
+    /// The indices are the ones given by `BinTrie::internal_indices`, which
+    /// gives the root as `0`. Returns `None` if there is no node at the index.
+    /// The count of a node that was abandoned by a removal is meaningless.
+    ///
+    /// ```
+    /// # use bintrie::WeightedBinTrie;
+    /// let trie = WeightedBinTrie::new();
+    /// assert_eq!(trie.subtree_size(0), Some(0));
+    /// assert_eq!(trie.subtree_size(1), None);
+    /// ```
+    pub fn subtree_size(&self, node: usize) -> Option<usize> {
+        self.counts.get(node).map(|&count| count as usize)
+    }
+
+    /// Picks a random item, with every item equally likely.
+    ///
+    /// Each side is taken with a probability proportional to the number of items
+    /// on it, so this takes one step per level, unlike `BinTrie::sample_uniform`.
+    ///
+    /// This requires the `rand` feature.
+    ///
+    /// ```
+    /// # use bintrie::WeightedBinTrie;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    /// let lookup = |n: u32, l: u32| (n >> l) & 1 == 1;
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let mut trie = WeightedBinTrie::new_depth(32);
+    /// for &n in &[0, 1, 3, 5, 7] {
+    ///     trie.insert(n, |l| lookup(n, l), lookup);
+    /// }
+    /// let zeros = (0..1000).filter(|_| trie.sample_uniform(&mut rng) == Some(0)).count();
+    /// assert!(zeros > 120 && zeros < 280);
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample_uniform<R>(&self, rng: &mut R) -> Option<u32>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let mut slot = 0;
+        while slot & HIGH == 0 {
+            let [a, b] = self.trie.internals[slot as usize].0;
+            slot = if rng.gen_range(0..self.count(a) + self.count(b)) < self.count(a) {
+                a
+            } else {
+                b
+            };
+        }
+        Some(self.trie.untag(slot))
+    }
+
+    /// Gets the slot on the given side of the internal node `slot`.
+    fn child(&self, slot: u32, side: bool) -> u32 {
+        self.trie.internals[slot as usize].0[if side { 1 } else { 0 }]
+    }
+
+    /// The number of items in the slot of a child.
+    fn count(&self, slot: u32) -> u32 {
+        match slot {
+            0 => 0,
+            n if n & HIGH != 0 => 1,
+            n => self.counts[n as usize],
+        }
+    }
+}
+
+impl Default for WeightedBinTrie {
+    fn default() -> Self {
+        Self::new_depth(8192)
+    }
+}